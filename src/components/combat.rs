@@ -0,0 +1,76 @@
+use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use crate::dice::Dice;
+use std::str::FromStr;
+
+// What an object brings to a fight. Damage is dice notation, e.g. "1d6+1";
+// attack is added to each damage roll and defense taken off. The chance to
+// hit starts at BASE_HIT_CHANCE and moves with accuracy against evasion.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CombatStats {
+    pub damage: String,
+    pub attack: i32,
+    pub defense: i32,
+    pub accuracy: i32,
+    pub evasion: i32
+}
+
+// percent, before accuracy and evasion
+pub const BASE_HIT_CHANCE: i32 = 75;
+
+impl IsComponent for CombatStats {
+    fn get_type_name(&self) -> String {
+        String::from_str("CombatStats").unwrap()
+    }
+}
+
+impl Default for CombatStats {
+    // a single point of damage, as before objects had stats
+    fn default() -> Self {
+        Self {
+            damage: "1".to_string(),
+            attack: 0,
+            defense: 0,
+            accuracy: 0,
+            evasion: 0
+        }
+    }
+}
+
+impl CombatStats {
+    pub fn damage_dice(&self) -> Result<Dice> {
+        self.damage.parse()
+    }
+
+    /// Percent chance of this landing a blow on `target`. Nothing is
+    /// certain either way.
+    pub fn hit_chance(&self, target: &CombatStats) -> i32 {
+        (BASE_HIT_CHANCE + self.accuracy - target.evasion).clamp(5, 95)
+    }
+}
+
+// Marks what fights the player, as opposed to anything else with health,
+// so it's what gets aimed at.
+#[derive(Serialize, Deserialize)]
+pub struct Hostile;
+
+impl IsComponent for Hostile {
+    fn get_type_name(&self) -> String {
+        String::from_str("Hostile").unwrap()
+    }
+}
+
+// Where rolls made during play come from, so a seed replays the same fights.
+#[derive(Serialize, Deserialize)]
+pub struct GameRng {
+    pub seed: u64,
+    // how many rngs have been handed out
+    pub draws: u64
+}
+
+impl IsComponent for GameRng {
+    fn get_type_name(&self) -> String {
+        String::from_str("GameRng").unwrap()
+    }
+}
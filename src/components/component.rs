@@ -1,4 +1,4 @@
-use serde::{Serialize, Deserialize};
+use serde::Serialize;
 use serde::de::DeserializeOwned;
 
 // A Component represents any component of a GameObject.
@@ -25,7 +25,7 @@ impl Component {
         self.data = serde_json::to_string(item).unwrap();
     }
 
-    pub fn extract_data<T>(&mut self) -> T
+    pub fn extract_data<T>(&self) -> T
     where T: DeserializeOwned + IsComponent,
     {
         return serde_json::from_str(self.data.as_str()).unwrap();
@@ -22,4 +22,30 @@ impl IsComponent for TextBox {
     fn get_type_name(&self) -> String {
         String::from_str("TextBox").unwrap()
     }
-}
\ No newline at end of file
+}
+
+// A labelled bar, e.g. a health bar. ratio is between 0 and 1.
+#[derive(Serialize, Deserialize)]
+pub struct GaugeBox {
+    pub label: String,
+    pub ratio: f64
+}
+
+impl IsComponent for GaugeBox {
+    fn get_type_name(&self) -> String {
+        String::from_str("GaugeBox").unwrap()
+    }
+}
+
+// A labelled sparkline over the most recent values of something.
+#[derive(Serialize, Deserialize)]
+pub struct SparklineBox {
+    pub label: String,
+    pub data: Vec<u64>
+}
+
+impl IsComponent for SparklineBox {
+    fn get_type_name(&self) -> String {
+        String::from_str("SparklineBox").unwrap()
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Serialize, Deserialize};
+use crate::components::{CombatStats, IsComponent};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armour,
+    Ring
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Weapon, EquipSlot::Armour, EquipSlot::Ring];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "weapon",
+            EquipSlot::Armour => "armour",
+            EquipSlot::Ring => "ring"
+        }
+    }
+
+    // e.g. "The player wields the dagger."
+    pub fn verb(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "wields",
+            EquipSlot::Armour => "wears",
+            EquipSlot::Ring => "puts on"
+        }
+    }
+
+    // e.g. "The player puts away the dagger."
+    pub fn removal_verb(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "puts away",
+            _ => "takes off"
+        }
+    }
+}
+
+// An item which can be wielded or worn in `slot`, and what it adds to its
+// wearer's stats while it is. A weapon's damage replaces the wearer's own.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Equippable {
+    pub slot: EquipSlot,
+    pub damage: Option<String>,
+    pub attack: i32,
+    pub defense: i32,
+    pub accuracy: i32,
+    pub evasion: i32,
+    pub max_health: i32
+}
+
+impl IsComponent for Equippable {
+    fn get_type_name(&self) -> String {
+        String::from_str("Equippable").unwrap()
+    }
+}
+
+impl Equippable {
+    // no bonuses, to be filled in with struct update syntax
+    pub fn new(slot: EquipSlot) -> Self {
+        Self { slot, damage: None, attack: 0, defense: 0, accuracy: 0, evasion: 0, max_health: 0 }
+    }
+
+    pub fn apply(&self, stats: &mut CombatStats) {
+        if let Some(damage) = &self.damage {
+            stats.damage = damage.clone();
+        }
+        stats.attack += self.attack;
+        stats.defense += self.defense;
+        stats.accuracy += self.accuracy;
+        stats.evasion += self.evasion;
+    }
+
+    /// The bonuses as they'd be listed, e.g. "1d8, +2 defense".
+    pub fn describe(&self) -> String {
+        let mut parts = vec![];
+        if let Some(damage) = &self.damage {
+            parts.push(format!("{} damage", damage));
+        }
+        for (value, name) in [
+            (self.attack, "attack"), (self.defense, "defense"), (self.accuracy, "accuracy"),
+            (self.evasion, "evasion"), (self.max_health, "max HP")
+        ] {
+            if value != 0 {
+                parts.push(format!("{:+} {}", value, name));
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+// The items an object has equipped, one per slot, by object id. Equipped
+// items stay in the Inventory too.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Equipment {
+    pub weapon: Option<String>,
+    pub armour: Option<String>,
+    pub ring: Option<String>
+}
+
+impl IsComponent for Equipment {
+    fn get_type_name(&self) -> String {
+        String::from_str("Equipment").unwrap()
+    }
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipSlot) -> Option<&String> {
+        match slot {
+            EquipSlot::Weapon => self.weapon.as_ref(),
+            EquipSlot::Armour => self.armour.as_ref(),
+            EquipSlot::Ring => self.ring.as_ref()
+        }
+    }
+
+    /// Puts `item` in the slot, returning whatever was there.
+    pub fn set(&mut self, slot: EquipSlot, item: Option<String>) -> Option<String> {
+        std::mem::replace(self.slot_mut(slot), item)
+    }
+
+    /// The slot `item` is equipped in, if it is.
+    pub fn slot_of(&self, item: &str) -> Option<EquipSlot> {
+        EquipSlot::ALL.into_iter().find(|s| self.get(*s).is_some_and(|i| i == item))
+    }
+
+    pub fn items(&self) -> Vec<String> {
+        EquipSlot::ALL.iter().filter_map(|s| self.get(*s).cloned()).collect()
+    }
+
+    fn slot_mut(&mut self, slot: EquipSlot) -> &mut Option<String> {
+        match slot {
+            EquipSlot::Weapon => &mut self.weapon,
+            EquipSlot::Armour => &mut self.armour,
+            EquipSlot::Ring => &mut self.ring
+        }
+    }
+}
+
+// A weapon which hits from afar, along a line of fire up to `range` tiles.
+// Bows are fired while wielded; thrown ones leave the hand and land where
+// they stop.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Ranged {
+    pub range: u16,
+    pub thrown: bool
+}
+
+impl IsComponent for Ranged {
+    fn get_type_name(&self) -> String {
+        String::from_str("Ranged").unwrap()
+    }
+}
+
+impl Ranged {
+    // e.g. "The player fires the short bow."
+    pub fn verb(&self) -> &'static str {
+        if self.thrown { "throws" } else { "fires" }
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use std::str::FromStr;
+
+// The tiles of a level the player has seen, kept on its map_object. Only
+// those are drawn. Levels without one are drawn in full.
+#[derive(Serialize, Deserialize)]
+pub struct Explored {
+    pub width: u16,
+    // indexed [y * width + x]
+    pub seen: Vec<bool>
+}
+
+// how far the player can see, in tiles
+pub const SIGHT_RADIUS: u16 = 8;
+
+impl IsComponent for Explored {
+    fn get_type_name(&self) -> String {
+        String::from_str("Explored").unwrap()
+    }
+}
+
+impl Explored {
+    /// Nothing seen yet, on a map of this size.
+    pub fn new(size: (u16, u16)) -> Self {
+        Self { width: size.0, seen: vec![false; usize::from(size.0) * usize::from(size.1)] }
+    }
+
+    fn index(&self, pos: (u16, u16)) -> Option<usize> {
+        if pos.0 >= self.width {
+            return None;
+        }
+        let i = usize::from(pos.1) * usize::from(self.width) + usize::from(pos.0);
+        if i < self.seen.len() { Some(i) } else { None }
+    }
+
+    pub fn is_seen(&self, pos: (u16, u16)) -> bool {
+        self.index(pos).is_some_and(|i| self.seen[i])
+    }
+
+    pub fn see(&mut self, pos: (u16, u16)) {
+        if let Some(i) = self.index(pos) {
+            self.seen[i] = true;
+        }
+    }
+
+    pub fn see_all(&mut self) {
+        self.seen.fill(true);
+    }
+}
@@ -8,8 +8,41 @@ pub struct Health {
     pub max_health: u16
 }
 
+impl Health {
+    /// Takes off up to `amount`, stopping at zero. Returns what was lost.
+    pub fn damage(&mut self, amount: u16) -> u16 {
+        let lost = amount.min(self.current_health);
+        self.current_health -= lost;
+        lost
+    }
+
+    /// Adds up to `amount`, stopping at max_health. Returns what was gained.
+    pub fn heal(&mut self, amount: u16) -> u16 {
+        let gained = amount.min(self.max_health.saturating_sub(self.current_health));
+        self.current_health += gained;
+        gained
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current_health == 0
+    }
+}
+
 impl IsComponent for Health {
     fn get_type_name(&self) -> String {
         String::from_str("Health").unwrap()
     }
+}
+
+// Opt-in bar drawn over an entity with Health. It stays hidden until the
+// entity is hit or targeted, and goes away with the entity.
+#[derive(Serialize, Deserialize)]
+pub struct HealthBar {
+    pub visible: bool
+}
+
+impl IsComponent for HealthBar {
+    fn get_type_name(&self) -> String {
+        String::from_str("HealthBar").unwrap()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use std::str::FromStr;
+use std::collections::BTreeMap;
+
+// Scrolling log of game messages, fed by game.message events. Only the
+// newest max_messages are kept; the renderer shows as many as fit.
+#[derive(Serialize, Deserialize)]
+pub struct MessageLog {
+    pub messages: Vec<String>,
+    pub max_messages: usize
+}
+
+impl IsComponent for MessageLog {
+    fn get_type_name(&self) -> String {
+        String::from_str("MessageLog").unwrap()
+    }
+}
+
+impl MessageLog {
+    pub fn new(max_messages: usize) -> Self {
+        Self {
+            messages: vec![],
+            max_messages
+        }
+    }
+
+    pub fn push(&mut self, message: String) {
+        self.messages.push(message);
+        if self.messages.len() > self.max_messages {
+            let overflow = self.messages.len() - self.max_messages;
+            self.messages.drain(0..overflow);
+        }
+    }
+}
+
+// Progress information shown in the status sidebar.
+#[derive(Serialize, Deserialize)]
+pub struct Status {
+    pub level: u16,
+    pub depth: u16,
+    pub turn: u32
+}
+
+impl IsComponent for Status {
+    fn get_type_name(&self) -> String {
+        String::from_str("Status").unwrap()
+    }
+}
+
+// Put on the "game" object when the game ends. Its presence stops play and
+// its fields fill in the game over screen and morgue file.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameOver {
+    // e.g. "killed by the enemy"
+    pub cause: String,
+    pub turn: u32,
+    pub depth: u16,
+    // the player as they were at the end, see morgue::character_sheet
+    pub sheet: Vec<String>
+}
+
+impl IsComponent for GameOver {
+    fn get_type_name(&self) -> String {
+        String::from_str("GameOver").unwrap()
+    }
+}
+
+// Who an object has killed, by name, for the morgue file.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Kills {
+    pub counts: BTreeMap<String, u32>
+}
+
+impl IsComponent for Kills {
+    fn get_type_name(&self) -> String {
+        String::from_str("Kills").unwrap()
+    }
+}
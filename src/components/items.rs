@@ -0,0 +1,134 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use std::str::FromStr;
+
+// Marks an object as an item. Items lie on the map with a WorldPosition and
+// a Glyph until something picks them up.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub description: String
+}
+
+impl IsComponent for Item {
+    fn get_type_name(&self) -> String {
+        String::from_str("Item").unwrap()
+    }
+}
+
+// An item which can be picked up and carried. Stackable items carried with
+// another of the same name are merged into one object with a higher count.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Carryable {
+    // of each one in the stack
+    pub weight: u16,
+    pub count: u16,
+    pub stacks: bool
+}
+
+impl Carryable {
+    pub fn total_weight(&self) -> u16 {
+        self.weight.saturating_mul(self.count)
+    }
+}
+
+impl IsComponent for Carryable {
+    fn get_type_name(&self) -> String {
+        String::from_str("Carryable").unwrap()
+    }
+}
+
+// The items an object is carrying, by object id, oldest first. Carried
+// items keep their components but lose their WorldPosition.
+#[derive(Serialize, Deserialize)]
+pub struct Inventory {
+    pub items: Vec<String>,
+    // how many stacks fit; see slots
+    pub capacity: usize,
+    pub max_weight: u16
+}
+
+pub const PACK_CAPACITY: usize = 26;
+pub const PACK_WEIGHT: u16 = 40;
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self { items: vec![], capacity: PACK_CAPACITY, max_weight: PACK_WEIGHT }
+    }
+}
+
+// what items are listed under, in order
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+impl Inventory {
+    /// How many stacks fit: the capacity, but never more than there are
+    /// letters to list them under.
+    pub fn slots(&self) -> usize {
+        self.capacity.min(LETTERS.len())
+    }
+
+    /// The letter the item at `index` is listed under, a to z then A to Z.
+    pub fn letter(index: usize) -> Option<char> {
+        LETTERS.chars().nth(index)
+    }
+
+    /// The index of the item listed under `letter`, if there is one.
+    pub fn index_of(&self, letter: char) -> Option<usize> {
+        let index = LETTERS.find(letter)?;
+        if index < self.items.len().min(self.slots()) { Some(index) } else { None }
+    }
+}
+
+impl IsComponent for Inventory {
+    fn get_type_name(&self) -> String {
+        String::from_str("Inventory").unwrap()
+    }
+}
+
+// An item used up by using it, one from the stack at a time. Its effect is
+// the script registered as `script` (see scripts::effect_scripts), sent a
+// game.effect for whoever uses it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Consumable {
+    pub script: String,
+    // how strong the effect is, e.g. how much it heals
+    pub power: u16,
+    // e.g. "drinks", for "The player drinks the healing potion."
+    pub verb: String
+}
+
+impl IsComponent for Consumable {
+    fn get_type_name(&self) -> String {
+        String::from_str("Consumable").unwrap()
+    }
+}
+
+// Keeps an object from attacking for a while.
+#[derive(Serialize, Deserialize)]
+pub struct Afraid {
+    pub turns: u16
+}
+
+impl IsComponent for Afraid {
+    fn get_type_name(&self) -> String {
+        String::from_str("Afraid").unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_run_a_to_z_then_capitals() {
+        let items = (0..60).map(|i| format!("item{}", i)).collect();
+        let inventory = Inventory { items, capacity: 100, max_weight: PACK_WEIGHT };
+        assert_eq!(inventory.slots(), 52);
+        assert_eq!((Inventory::letter(0), Inventory::letter(26), Inventory::letter(52)), (Some('a'), Some('A'), None));
+        assert_eq!((inventory.index_of('z'), inventory.index_of('Z')), (Some(25), Some(51)));
+        // past z and Z, and items past the last slot
+        assert_eq!((inventory.index_of('{'), inventory.index_of('[')), (None, None));
+        let small = Inventory { items: vec!["a".to_string()], ..Inventory::default() };
+        assert_eq!((small.index_of('a'), small.index_of('b')), (Some(0), None));
+    }
+}
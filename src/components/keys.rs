@@ -0,0 +1,25 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use std::str::FromStr;
+
+// Marks an object lying on the map as a key. Walking onto it picks it up.
+#[derive(Serialize, Deserialize)]
+pub struct Key {}
+
+impl IsComponent for Key {
+    fn get_type_name(&self) -> String {
+        String::from_str("Key").unwrap()
+    }
+}
+
+// Keys carried by an object. Any key fits any lock, and is used up by it.
+#[derive(Serialize, Deserialize)]
+pub struct Keys {
+    pub count: u16
+}
+
+impl IsComponent for Keys {
+    fn get_type_name(&self) -> String {
+        String::from_str("Keys").unwrap()
+    }
+}
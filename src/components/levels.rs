@@ -0,0 +1,21 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use crate::mapgen::StyleOptions;
+use std::str::FromStr;
+
+// How to build the levels of the dungeon which haven't been visited yet.
+// Each level's TileMap is kept on the object named by map_object.
+#[derive(Serialize, Deserialize)]
+pub struct Levels {
+    pub seed: u64,
+    pub map_style: String,
+    pub style: StyleOptions,
+    // in the prefab file format, see assets/vaults.txt
+    pub vaults: String
+}
+
+impl IsComponent for Levels {
+    fn get_type_name(&self) -> String {
+        String::from_str("Levels").unwrap()
+    }
+}
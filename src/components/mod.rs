@@ -1,13 +1,33 @@
 pub use self::component::{Component, IsComponent};
-pub use self::tile_map::{TileMap, TileType};
+pub use self::tile_map::{TileMap, TileType, map_object, line_between};
 pub use self::positions::{WorldPosition, ScreenPosition, Vector2};
-pub use self::display::{Glyph, TextBox};
-pub use self::health::Health;
-pub use self::monitor::Monitor;
+pub use self::point::{Point, Direction};
+pub use self::display::{Glyph, TextBox, GaugeBox, SparklineBox};
+pub use self::health::{Health, HealthBar};
+pub use self::monitor::{Monitor, MonitorKind};
+pub use self::hud::{MessageLog, Status, GameOver, Kills};
+pub use self::keys::{Key, Keys};
+pub use self::levels::Levels;
+pub use self::combat::{CombatStats, GameRng, Hostile};
+pub use self::items::{Item, Carryable, Inventory, Consumable, Afraid};
+pub use self::screens::{InputContext, InventoryScreen, TargetScreen};
+pub use self::equipment::{EquipSlot, Equippable, Equipment, Ranged};
+pub use self::explored::{Explored, SIGHT_RADIUS};
+pub use self::status_effects::{EffectKind, StatusEffect, StatusEffects, Actions};
 
 mod component;
 mod tile_map;
 mod positions;
+mod point;
 mod display;
 mod health;
-mod monitor;
\ No newline at end of file
+mod monitor;
+mod hud;
+mod keys;
+mod levels;
+mod combat;
+mod items;
+mod screens;
+mod equipment;
+mod explored;
+mod status_effects;
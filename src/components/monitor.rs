@@ -1,19 +1,50 @@
 use serde::{Serialize, Deserialize};
 use crate::components::IsComponent;
 use std::str::FromStr;
-use std::collections::HashMap;
 
 // A monitor is an information component which helps you fetch data about
 // some other component. It's intended for use with UI components which
 // display information e.g. a health bar.
+//
+// Each binding names a path of the form object.Component.field (fields may
+// be nested, e.g. player.Status.turn). The bound values are substituted
+// into the template wherever {name} appears.
 #[derive(Serialize, Deserialize)]
 pub struct Monitor {
-    // object name, component type
-    pub to_monitor: Vec<(String, String)>
+    // binding name, path
+    pub bindings: Vec<(String, String)>,
+    pub template: String,
+    pub kind: MonitorKind
+}
+
+// Which display component the monitor writes to on the same object.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MonitorKind {
+    // TextBox showing the filled in template
+    Text,
+    // GaugeBox filled by the ratio of two bindings, labelled by the template
+    Gauge { value: String, max: String },
+    // SparklineBox keeping the last `length` values of a binding
+    Sparkline { value: String, length: usize }
 }
 
 impl IsComponent for Monitor {
     fn get_type_name(&self) -> String {
         String::from_str("Monitor").unwrap()
     }
-}
\ No newline at end of file
+}
+
+impl Monitor {
+    pub fn new(template: &str, kind: MonitorKind) -> Self {
+        Self {
+            bindings: vec![],
+            template: template.to_string(),
+            kind
+        }
+    }
+
+    pub fn bind(mut self, name: &str, path: &str) -> Self {
+        self.bindings.push((name.to_string(), path.to_string()));
+        self
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Serialize, Deserialize};
+
+// A tile coordinate. Moving one never wraps or panics: the offset helpers
+// give None instead of stepping off the edge of u16 or of a map.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Point {
+    pub x: u16,
+    pub y: u16
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [Direction::North, Direction::South, Direction::East, Direction::West];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::North, Direction::South, Direction::East, Direction::West,
+        Direction::NorthEast, Direction::NorthWest, Direction::SouthEast, Direction::SouthWest
+    ];
+
+    /// (dx, dy), with y growing down the screen.
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+            Direction::NorthEast => (1, -1),
+            Direction::NorthWest => (-1, -1),
+            Direction::SouthEast => (1, 1),
+            Direction::SouthWest => (-1, 1)
+        }
+    }
+}
+
+impl Point {
+    pub fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    /// One step in `dir`, or None if that would leave the range of u16.
+    pub fn offset(&self, dir: Direction) -> Option<Point> {
+        self.offset_by(dir.delta())
+    }
+
+    pub fn offset_by(&self, delta: (i32, i32)) -> Option<Point> {
+        let x = u16::try_from(i32::from(self.x) + delta.0).ok()?;
+        let y = u16::try_from(i32::from(self.y) + delta.1).ok()?;
+        Some(Point { x, y })
+    }
+
+    /// Like offset, but also None if the step leaves a map of this size.
+    pub fn offset_within(&self, dir: Direction, size: (u16, u16)) -> Option<Point> {
+        self.offset(dir).filter(|p| p.x < size.0 && p.y < size.1)
+    }
+
+    pub fn as_tuple(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+}
+
+impl From<(u16, u16)> for Point {
+    fn from(pos: (u16, u16)) -> Self {
+        Point { x: pos.0, y: pos.1 }
+    }
+}
+
+impl From<Point> for (u16, u16) {
+    fn from(p: Point) -> Self {
+        (p.x, p.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn direction() -> impl Strategy<Value = Direction> {
+        proptest::sample::select(Direction::ALL.to_vec())
+    }
+
+    #[test]
+    fn steps_off_the_edge_are_refused() {
+        assert_eq!(Point::new(0, 5).offset(Direction::West), None);
+        assert_eq!(Point::new(5, u16::MAX).offset(Direction::South), None);
+        assert_eq!(Point::new(4, 4).offset_within(Direction::East, (5, 5)), None);
+        assert_eq!(Point::new(4, 4).offset(Direction::NorthWest), Some(Point::new(3, 3)));
+    }
+
+    proptest! {
+        #[test]
+        fn offsets_move_exactly_one_step(x: u16, y: u16, dir in direction()) {
+            let p = Point::new(x, y);
+            if let Some(q) = p.offset(dir) {
+                let (dx, dy) = dir.delta();
+                prop_assert_eq!(i32::from(q.x) - i32::from(x), dx);
+                prop_assert_eq!(i32::from(q.y) - i32::from(y), dy);
+            }
+        }
+
+        #[test]
+        fn offsets_within_stay_on_the_map(w in 1u16..50, h in 1u16..50, x: u16, y: u16, dir in direction()) {
+            let p = Point::new(x % w, y % h);
+            if let Some(q) = p.offset_within(dir, (w, h)) {
+                prop_assert!(q.x < w && q.y < h);
+            }
+        }
+    }
+}
@@ -1,16 +1,20 @@
 use serde::{Serialize, Deserialize};
-use crate::components::IsComponent;
+use crate::components::{IsComponent, Point};
 use std::str::FromStr;
 
 pub trait Vector2 {
     fn as_tuple_2(&self) -> (u16, u16);
+
+    fn as_point(&self) -> Point {
+        Point::from(self.as_tuple_2())
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct WorldPosition {
     pub x: u16,
     pub y: u16,
-    // this one is the id of the tilemap component
+    // the level it's on, whose TileMap is kept on map_object(map)
     pub map: u16 
 }
 
@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use std::str::FromStr;
+
+// Which screen key presses go to, kept on the "game" object. On the "map"
+// they're sent as input.key_press, and any other screen gets them as
+// input.<name> instead. Either way a turn only passes if one of the
+// scripts they reach sets turn_taken, see stats::take_turn.
+#[derive(Serialize, Deserialize)]
+pub struct InputContext {
+    pub name: String,
+    pub turn_taken: bool
+}
+
+impl Default for InputContext {
+    fn default() -> Self {
+        Self { name: "map".to_string(), turn_taken: false }
+    }
+}
+
+impl InputContext {
+    pub fn named(name: &str) -> Self {
+        Self { name: name.to_string(), turn_taken: false }
+    }
+}
+
+impl IsComponent for InputContext {
+    fn get_type_name(&self) -> String {
+        String::from_str("InputContext").unwrap()
+    }
+}
+
+// The open inventory screen, on the "game" object while it's showing.
+#[derive(Default, Serialize, Deserialize)]
+pub struct InventoryScreen {
+    // the item being looked at, or None for the whole list
+    pub selected: Option<String>
+}
+
+impl IsComponent for InventoryScreen {
+    fn get_type_name(&self) -> String {
+        String::from_str("InventoryScreen").unwrap()
+    }
+}
+
+// The targeting cursor, on the "game" object while the player is aiming
+// `item` at x, y.
+#[derive(Serialize, Deserialize)]
+pub struct TargetScreen {
+    pub item: String,
+    pub x: u16,
+    pub y: u16
+}
+
+impl IsComponent for TargetScreen {
+    fn get_type_name(&self) -> String {
+        String::from_str("TargetScreen").unwrap()
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Serialize, Deserialize};
+use crate::components::IsComponent;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum EffectKind {
+    // loses `potency` health a turn
+    Poison,
+    // gains `potency` health a turn
+    Regeneration,
+    // two actions a turn
+    Haste,
+    // moves sometimes go the wrong way
+    Confusion,
+    // sees only the next tiles, and fights worse
+    Blindness
+}
+
+// What happens when an effect is added to one of the same kind already
+// running.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stacking {
+    // potencies add up, and it lasts as long as the longer of the two
+    Intensity,
+    // the turns add up
+    Duration,
+    // the stronger and longer of each is kept
+    Refresh
+}
+
+impl EffectKind {
+    pub fn stacking(&self) -> Stacking {
+        match self {
+            EffectKind::Poison => Stacking::Intensity,
+            EffectKind::Confusion | EffectKind::Blindness => Stacking::Duration,
+            EffectKind::Regeneration | EffectKind::Haste => Stacking::Refresh
+        }
+    }
+
+    // e.g. "died of poison"
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectKind::Poison => "poison",
+            EffectKind::Regeneration => "regeneration",
+            EffectKind::Haste => "haste",
+            EffectKind::Confusion => "confusion",
+            EffectKind::Blindness => "blindness"
+        }
+    }
+
+    // shown in the status sidebar
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            EffectKind::Poison => "Poisoned",
+            EffectKind::Regeneration => "Regen",
+            EffectKind::Haste => "Hasted",
+            EffectKind::Confusion => "Confused",
+            EffectKind::Blindness => "Blind"
+        }
+    }
+
+    // e.g. "The player is poisoned."
+    pub fn started(&self) -> &'static str {
+        match self {
+            EffectKind::Poison => "is poisoned",
+            EffectKind::Regeneration => "starts to regenerate",
+            EffectKind::Haste => "speeds up",
+            EffectKind::Confusion => "is confused",
+            EffectKind::Blindness => "is blinded"
+        }
+    }
+
+    // e.g. "The player is no longer poisoned."
+    pub fn ended(&self) -> &'static str {
+        match self {
+            EffectKind::Poison => "is no longer poisoned",
+            EffectKind::Regeneration => "stops regenerating",
+            EffectKind::Haste => "slows down",
+            EffectKind::Confusion => "is no longer confused",
+            EffectKind::Blindness => "can see again"
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    // left to run, counting down once a turn
+    pub turns: u16,
+    pub potency: u16
+}
+
+// The timed effects on an object, at most one of each kind. On items, the
+// effects using the item gives.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>
+}
+
+impl IsComponent for StatusEffects {
+    fn get_type_name(&self) -> String {
+        String::from_str("StatusEffects").unwrap()
+    }
+}
+
+impl StatusEffects {
+    pub fn get(&self, kind: EffectKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|e| e.kind == kind)
+    }
+
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.get(kind).is_some()
+    }
+
+    /// Adds the effect, combining it with one of the same kind by its
+    /// kind's Stacking. Returns whether it's new rather than combined.
+    pub fn add(&mut self, effect: StatusEffect) -> bool {
+        let current = match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            None => {
+                self.effects.push(effect);
+                return true;
+            },
+            Some(e) => e
+        };
+        match effect.kind.stacking() {
+            Stacking::Intensity => {
+                current.potency = current.potency.saturating_add(effect.potency);
+                current.turns = current.turns.max(effect.turns);
+            },
+            Stacking::Duration => {
+                current.turns = current.turns.saturating_add(effect.turns);
+                current.potency = current.potency.max(effect.potency);
+            },
+            Stacking::Refresh => {
+                current.turns = current.turns.max(effect.turns);
+                current.potency = current.potency.max(effect.potency);
+            }
+        }
+        false
+    }
+
+    /// Counts every effect down a turn, returning those which have run out.
+    pub fn tick(&mut self) -> Vec<StatusEffect> {
+        for effect in &mut self.effects {
+            effect.turns = effect.turns.saturating_sub(1);
+        }
+        let (ended, running) = self.effects.iter().partition(|e| e.turns == 0);
+        self.effects = running;
+        ended
+    }
+}
+
+// How many actions an object has taken so far this turn, kept while it
+// has more to come or has been slowed. See stats::end_action.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Actions {
+    pub taken: u8,
+    // turns owed on top of the usual one, e.g. for wading through water
+    pub slowed: u16
+}
+
+impl IsComponent for Actions {
+    fn get_type_name(&self) -> String {
+        String::from_str("Actions").unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(kind: EffectKind, turns: u16, potency: u16) -> StatusEffect {
+        StatusEffect { kind, turns, potency }
+    }
+
+    #[test]
+    fn effects_stack_by_their_rule() {
+        let mut effects = StatusEffects::default();
+        assert!(effects.add(effect(EffectKind::Poison, 5, 1)));
+        assert!(!effects.add(effect(EffectKind::Poison, 3, 2)));
+        assert_eq!(effects.get(EffectKind::Poison), Some(&effect(EffectKind::Poison, 5, 3)));
+
+        effects.add(effect(EffectKind::Confusion, 4, 0));
+        effects.add(effect(EffectKind::Confusion, 4, 0));
+        assert_eq!(effects.get(EffectKind::Confusion).unwrap().turns, 8);
+
+        effects.add(effect(EffectKind::Haste, 10, 0));
+        effects.add(effect(EffectKind::Haste, 6, 0));
+        assert_eq!(effects.get(EffectKind::Haste).unwrap().turns, 10);
+        assert_eq!(effects.effects.len(), 3);
+    }
+
+    #[test]
+    fn effects_run_out() {
+        let mut effects = StatusEffects::default();
+        effects.add(effect(EffectKind::Blindness, 1, 0));
+        effects.add(effect(EffectKind::Regeneration, 2, 1));
+        assert_eq!(effects.tick(), [effect(EffectKind::Blindness, 0, 0)]);
+        assert!(effects.has(EffectKind::Regeneration) && !effects.has(EffectKind::Blindness));
+        assert_eq!(effects.tick().len(), 1);
+        assert!(effects.effects.is_empty());
+    }
+}
@@ -2,6 +2,8 @@ use serde::{Serialize, Deserialize};
 use crate::components::IsComponent;
 use ratatui::layout::Rect;
 use std::str::FromStr;
+use std::collections::HashMap;
+use anyhow::{anyhow, Result};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TileMap {
@@ -9,10 +11,198 @@ pub struct TileMap {
     size: (u16, u16)
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
 pub enum TileType {
     FLOOR,
-    WALL
+    WALL,
+    DOOR,
+    OPEN_DOOR,
+    LOCKED_DOOR,
+    WATER,
+    LAVA,
+    GRASS,
+    RUBBLE,
+    GLASS,
+    STAIRS_DOWN,
+    STAIRS_UP
+}
+
+// Everything the game needs to know about a kind of tile. Code should ask
+// these rather than matching on TileType, so new tiles work everywhere.
+pub struct TileDef {
+    pub walkable: bool,
+    // whether it can be seen through
+    pub transparent: bool,
+    // whether arrows and thrown things fly over it
+    pub open_to_missiles: bool,
+    // drawn on screen
+    pub glyph: char,
+    // used in plain text maps, see TileMap::from_ascii
+    pub ascii: char,
+    // turns it takes to cross, for pathfinding
+    pub cost: u32,
+    pub description: &'static str
+}
+
+impl TileType {
+    pub const ALL: [TileType; 12] = [
+        TileType::FLOOR, TileType::WALL, TileType::DOOR, TileType::OPEN_DOOR,
+        TileType::LOCKED_DOOR, TileType::WATER, TileType::LAVA, TileType::GRASS,
+        TileType::RUBBLE, TileType::GLASS, TileType::STAIRS_DOWN, TileType::STAIRS_UP
+    ];
+
+    pub fn def(&self) -> &'static TileDef {
+        match self {
+            TileType::FLOOR => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: '.', ascii: '.', cost: 1,
+                description: "the stone floor"
+            },
+            TileType::WALL => &TileDef {
+                walkable: false, transparent: false, open_to_missiles: false, glyph: '█', ascii: '#', cost: 0,
+                description: "a rough stone wall"
+            },
+            // closed doors are costed for pathfinding, as they can be opened
+            TileType::DOOR => &TileDef {
+                walkable: false, transparent: false, open_to_missiles: false, glyph: '+', ascii: '+', cost: 1,
+                description: "a wooden door"
+            },
+            TileType::OPEN_DOOR => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: '\'', ascii: '\'', cost: 1,
+                description: "an open door"
+            },
+            // looks like any other door until you try it
+            TileType::LOCKED_DOOR => &TileDef {
+                walkable: false, transparent: false, open_to_missiles: false, glyph: '+', ascii: '&', cost: 1,
+                description: "a locked door"
+            },
+            TileType::WATER => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: '~', ascii: '~', cost: 2,
+                description: "shallow water"
+            },
+            TileType::LAVA => &TileDef {
+                walkable: false, transparent: true, open_to_missiles: true, glyph: '≈', ascii: '=', cost: 0,
+                description: "a river of lava"
+            },
+            TileType::GRASS => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: '"', ascii: '"', cost: 1,
+                description: "long grass"
+            },
+            TileType::RUBBLE => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: ',', ascii: ',', cost: 2,
+                description: "a pile of rubble"
+            },
+            TileType::GLASS => &TileDef {
+                walkable: false, transparent: true, open_to_missiles: false, glyph: '▒', ascii: '%', cost: 0,
+                description: "a pane of thick glass"
+            },
+            TileType::STAIRS_DOWN => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: '>', ascii: '>', cost: 1,
+                description: "a staircase leading down"
+            },
+            TileType::STAIRS_UP => &TileDef {
+                walkable: true, transparent: true, open_to_missiles: true, glyph: '<', ascii: '<', cost: 1,
+                description: "a staircase leading up"
+            }
+        }
+    }
+
+    pub fn is_walkable(&self) -> bool {
+        self.def().walkable
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.def().transparent
+    }
+
+    pub fn is_open_to_missiles(&self) -> bool {
+        self.def().open_to_missiles
+    }
+
+    pub fn glyph(&self) -> char {
+        self.def().glyph
+    }
+
+    pub fn ascii(&self) -> char {
+        self.def().ascii
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.def().cost
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.def().description
+    }
+
+    /// What a door becomes when it's opened. Locked doors need a key first.
+    pub fn opened(&self) -> Option<TileType> {
+        match self {
+            TileType::DOOR | TileType::LOCKED_DOOR => Some(TileType::OPEN_DOOR),
+            _ => None
+        }
+    }
+
+    pub fn closed(&self) -> Option<TileType> {
+        match self {
+            TileType::OPEN_DOOR => Some(TileType::DOOR),
+            _ => None
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        *self == TileType::LOCKED_DOOR
+    }
+
+    /// Walkable, or a door that could be opened. Used when working out which
+    /// parts of a map are connected.
+    pub fn is_passable(&self) -> bool {
+        self.is_walkable() || self.opened().is_some()
+    }
+
+    pub fn from_ascii(ch: char) -> Option<TileType> {
+        TileType::ALL.into_iter().find(|t| t.ascii() == ch)
+    }
+}
+
+impl FromStr for TileType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        TileType::ALL.into_iter()
+            .find(|t| format!("{:?}", t).eq_ignore_ascii_case(s))
+            .ok_or(anyhow!("'{}' isn't a tile type", s))
+    }
+}
+
+/// The object each level's TileMap is kept on, picked by WorldPosition::map.
+pub fn map_object(map: u16) -> String {
+    format!("map{}", map)
+}
+
+/// The tiles from one to the other along Bresenham's line, both ends
+/// included.
+pub fn line_between(from: (u16, u16), to: (u16, u16)) -> Vec<(u16, u16)> {
+    let (x0, y0) = (i32::from(from.0), i32::from(from.1));
+    let (x1, y1) = (i32::from(to.0), i32::from(to.1));
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+
+    let mut line = vec![from];
+    while (x, y) != (x1, y1) {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        line.push((x as u16, y as u16));
+    }
+    line
 }
 
 impl IsComponent for TileMap {
@@ -25,7 +215,7 @@ impl TileMap {
     pub fn new(size : (u16, u16)) -> Self {
         Self {
             tiles: TileMap::instantiate_map(size),
-            size: size
+            size
         }
     }
 
@@ -45,10 +235,101 @@ impl TileMap {
         return tiles;
     }
 
+    /// Build a map from rows of text, one character per tile, using the
+    /// standard characters from TileType::ascii.
+    pub fn from_ascii(text: &str) -> Result<Self> {
+        let legend: HashMap<char, TileType> = TileType::ALL.iter().map(|t| (t.ascii(), *t)).collect();
+        TileMap::from_ascii_with(text, &legend)
+    }
+
+    /// Like from_ascii but with the given characters. Short rows are padded
+    /// out with wall.
+    pub fn from_ascii_with(text: &str, legend: &HashMap<char, TileType>) -> Result<Self> {
+        let rows: Vec<&str> = text.lines().collect();
+        let height = u16::try_from(rows.len())?;
+        let width = u16::try_from(rows.iter().map(|r| r.chars().count()).max().unwrap_or(0))?;
+
+        let mut map = TileMap::new((width, height));
+        map.draw_rect(&map.to_rect(), TileType::WALL, true);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                let tile = legend.get(&ch)
+                    .ok_or(anyhow!("row {}: '{}' isn't in the legend", y + 1, ch))?;
+                map.tiles[x][y] = *tile;
+            }
+        }
+        Ok(map)
+    }
+
+    /// One line per row, in the characters from TileType::ascii.
+    pub fn to_ascii(&self) -> String {
+        let mut text = String::new();
+        for y in 0..self.size.1 {
+            for x in 0..self.size.0 {
+                text.push(self.tile_at((x, y)).ascii());
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The tile at pos, or None off the edge of the map.
+    pub fn get(&self, pos: impl Into<(u16, u16)>) -> Option<TileType> {
+        let (x, y) = pos.into();
+        self.tiles.get(usize::from(x))?.get(usize::from(y)).copied()
+    }
+
+    pub fn contains(&self, pos: impl Into<(u16, u16)>) -> bool {
+        let (x, y) = pos.into();
+        x < self.size.0 && y < self.size.1
+    }
+
+    // panics off the map; use get where pos might not be on it
     pub fn tile_at(&self, pos : (u16, u16)) -> TileType {
         return self.tiles[usize::from(pos.0)][usize::from(pos.1)];
     }
 
+    pub fn set_tile(&mut self, pos : (u16, u16), tile: TileType) {
+        self.tiles[usize::from(pos.0)][usize::from(pos.1)] = tile;
+    }
+
+    /// Whether nothing opaque stands between the two tiles. The ends
+    /// themselves don't block, so a wall can be seen.
+    pub fn line_of_sight(&self, from: (u16, u16), to: (u16, u16)) -> bool {
+        let line = line_between(from, to);
+        let between = line.len().saturating_sub(2);
+        line.iter().skip(1).take(between).all(|pos| self.tile_at(*pos).is_transparent())
+    }
+
+    /// Every tile within `radius` of `from` with a line of sight to it.
+    pub fn visible_from(&self, from: (u16, u16), radius: u16) -> Vec<(u16, u16)> {
+        let r = i32::from(radius);
+        let mut seen = vec![];
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if dx * dx + dy * dy > r * r {
+                    continue;
+                }
+                let (x, y) = (i32::from(from.0) + dx, i32::from(from.1) + dy);
+                let pos = match (u16::try_from(x), u16::try_from(y)) {
+                    (Ok(x), Ok(y)) if self.contains((x, y)) => (x, y),
+                    _ => continue
+                };
+                if self.line_of_sight(from, pos) {
+                    seen.push(pos);
+                }
+            }
+        }
+        seen
+    }
+
+    /// The first tile of this type, scanning row by row.
+    pub fn find(&self, tile: TileType) -> Option<(u16, u16)> {
+        (0..self.size.1)
+            .flat_map(|y| (0..self.size.0).map(move |x| (x, y)))
+            .find(|pos| self.tile_at(*pos) == tile)
+    }
+
     pub fn get_size(&self) -> (u16, u16) {
         return self.size;
     }
@@ -61,18 +342,83 @@ impl TileMap {
         // remove anything out of bounds of tilemap
         let real_pos = self.to_rect().intersection(*pos);
 
-        for x in real_pos.left()..pos.right() {
-            for y in real_pos.top()..pos.bottom() {
-                if filled || (!filled && (
+        for x in real_pos.left()..real_pos.right() {
+            for y in real_pos.top()..real_pos.bottom() {
+                if filled || (
                     (x+1 == real_pos.right()) || 
                     (x == real_pos.left()) ||
                     (y+1 == real_pos.bottom()) ||
                     (y == real_pos.top())
-                )) {
+                ) {
                     self.tiles[usize::from(x)][usize::from(y)] = tile
                 }
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_tile_has_its_own_character() {
+        for tile in TileType::ALL {
+            assert_eq!(TileType::from_ascii(tile.ascii()), Some(tile));
+            assert_eq!(format!("{:?}", tile).parse::<TileType>().unwrap(), tile);
+            // only passable tiles can be crossed
+            assert_eq!(tile.is_passable(), tile.cost() > 0);
+        }
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn ascii_round_trips() {
+        let text = "#####\n#.+.#\n#'&~#\n#####\n";
+        let map = TileMap::from_ascii(text).unwrap();
+        assert_eq!(map.get_size(), (5, 4));
+        assert_eq!(map.tile_at((2, 2)), TileType::LOCKED_DOOR);
+        assert_eq!(map.tile_at((2, 1)), TileType::DOOR);
+        assert_eq!(map.to_ascii(), text);
+    }
+
+    #[test]
+    fn short_rows_are_padded_with_wall() {
+        let map = TileMap::from_ascii("#...\n#.\n").unwrap();
+        assert_eq!(map.to_ascii(), "#...\n#.##\n");
+    }
+
+    #[test]
+    fn sight_is_blocked_by_opaque_tiles() {
+        let map = TileMap::from_ascii("#######\n#..%..#\n#..#..#\n#######\n").unwrap();
+        assert!(map.line_of_sight((1, 1), (5, 1)));
+        assert!(!map.line_of_sight((1, 2), (5, 2)));
+        // the wall itself can be seen
+        assert!(map.line_of_sight((1, 2), (3, 2)));
+
+        let seen = map.visible_from((1, 2), 4);
+        assert!(seen.contains(&(3, 2)) && !seen.contains(&(4, 2)));
+        // out of range
+        assert!(seen.contains(&(4, 1)) && !seen.contains(&(5, 1)));
+    }
+
+    #[test]
+    fn doors_open_and_close() {
+        assert_eq!(TileType::DOOR.opened(), Some(TileType::OPEN_DOOR));
+        assert_eq!(TileType::LOCKED_DOOR.opened(), Some(TileType::OPEN_DOOR));
+        assert_eq!(TileType::OPEN_DOOR.closed(), Some(TileType::DOOR));
+        assert_eq!(TileType::FLOOR.opened(), None);
+        assert!(!TileType::DOOR.is_walkable() && TileType::DOOR.is_passable());
+        assert!(TileType::OPEN_DOOR.is_transparent() && !TileType::DOOR.is_transparent());
+        assert!(TileType::GLASS.is_transparent() && !TileType::GLASS.is_open_to_missiles());
+        assert!(!TileType::LAVA.is_walkable() && TileType::LAVA.is_open_to_missiles());
+    }
+
+    #[test]
+    fn get_is_none_off_the_map() {
+        let map = TileMap::new((3, 2));
+        assert_eq!(map.get((2, 1)), Some(TileType::FLOOR));
+        assert_eq!(map.get((3, 1)), None);
+        assert_eq!(map.get((0, u16::MAX)), None);
+        assert!(map.contains((2, 1)) && !map.contains((2, 2)));
+    }
+}
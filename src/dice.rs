@@ -0,0 +1,113 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{anyhow, Result};
+use rand::Rng;
+
+// Dice in the usual tabletop notation: "2d6+1" is two six sided dice plus
+// one. "d8" is one die and a bare number is a fixed amount.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Dice {
+    pub count: u16,
+    pub sides: u16,
+    pub bonus: i32
+}
+
+impl Dice {
+    /// Even the biggest dice, 65535d65535, stay in range: the total is kept
+    /// as an i64 and clamped to what an i32 can hold.
+    pub fn roll(&self, rng: &mut impl Rng) -> i32 {
+        let mut total = i64::from(self.bonus);
+        for _ in 0..self.count {
+            total += i64::from(rng.gen_range(1..=self.sides));
+        }
+        i32::try_from(total).unwrap_or(if total < 0 { i32::MIN } else { i32::MAX })
+    }
+}
+
+impl FromStr for Dice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let text: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let bad = || anyhow!("'{}' isn't dice, expected something like 2d6+1", s);
+        if text.is_empty() {
+            return Err(bad());
+        }
+
+        // split off the bonus, keeping its sign
+        let (dice, bonus) = match text.find(['+', '-']) {
+            None => (text.as_str(), 0),
+            Some(i) => (&text[..i], text[i..].parse::<i32>().map_err(|_| bad())?)
+        };
+
+        match dice.split_once(['d', 'D']) {
+            // a flat amount, e.g. "3"
+            None => {
+                let flat: i32 = if dice.is_empty() { 0 } else { dice.parse().map_err(|_| bad())? };
+                Ok(Dice { count: 0, sides: 0, bonus: flat.checked_add(bonus).ok_or_else(bad)? })
+            },
+            Some((count, sides)) => {
+                let count = if count.is_empty() { 1 } else { count.parse().map_err(|_| bad())? };
+                let sides: u16 = sides.parse().map_err(|_| bad())?;
+                if sides == 0 {
+                    return Err(bad());
+                }
+                Ok(Dice { count, sides, bonus })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.count == 0 {
+            return write!(f, "{}", self.bonus);
+        }
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.bonus {
+            0 => Ok(()),
+            b if b > 0 => write!(f, "+{}", b),
+            b => write!(f, "{}", b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn parses_common_notation() {
+        assert_eq!("2d6+1".parse::<Dice>().unwrap(), Dice { count: 2, sides: 6, bonus: 1 });
+        assert_eq!("d8".parse::<Dice>().unwrap(), Dice { count: 1, sides: 8, bonus: 0 });
+        assert_eq!("1d4 - 1".parse::<Dice>().unwrap(), Dice { count: 1, sides: 4, bonus: -1 });
+        assert_eq!("3".parse::<Dice>().unwrap(), Dice { count: 0, sides: 0, bonus: 3 });
+        for bad in ["", "d", "2d", "2d0", "xd6", "2d6+", "2d6+1d4", "2147483647+1", "70000d6"] {
+            assert!(bad.parse::<Dice>().is_err(), "{} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn huge_rolls_dont_overflow() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let huge = Dice { count: u16::MAX, sides: u16::MAX, bonus: i32::MAX };
+        assert_eq!(huge.roll(&mut rng), i32::MAX);
+        let low = Dice { count: 1, sides: 1, bonus: i32::MIN };
+        assert_eq!(low.roll(&mut rng), i32::MIN + 1);
+    }
+
+    proptest! {
+        #[test]
+        fn rolls_stay_in_range(count in 0u16..10, sides in 1u16..20, bonus in -10i32..10, seed: u64) {
+            let dice = Dice { count, sides, bonus };
+            // zero dice of any size are the same flat amount
+            prop_assert_eq!(dice.to_string().parse::<Dice>().unwrap().to_string(), dice.to_string());
+            let roll = dice.roll(&mut StdRng::seed_from_u64(seed));
+            let lowest = i32::from(count) + bonus;
+            let highest = i32::from(count) * i32::from(sides) + bonus;
+            prop_assert!(lowest <= roll && roll <= highest);
+        }
+    }
+}
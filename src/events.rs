@@ -12,14 +12,197 @@ pub struct InputData {
 #[derive(Serialize, Deserialize)]
 pub struct HitData {
     pub aggressor: String,
+    pub target: String,
+    // what was fired or thrown, if it wasn't a blow in melee
+    #[serde(default)]
+    pub weapon: Option<String>
+}
+
+// game.target, sent when something is singled out e.g. by a targeting
+// cursor. HitData can be read as TargetData too.
+#[derive(Serialize, Deserialize)]
+pub struct TargetData {
     pub target: String
 }
 
+// game.fire, sent when `actor` shoots or throws `item` at x, y of the level
+// it's on
+#[derive(Serialize, Deserialize)]
+pub struct FireData {
+    pub actor: String,
+    pub item: String,
+    pub x: u16,
+    pub y: u16
+}
+
+impl FireData {
+    pub fn event(self) -> GameEvent {
+        GameEvent {
+            ev_type: "game.fire".to_string(),
+            data: serde_json::to_string(&self).unwrap()
+        }
+    }
+}
+
+// game.spawn, sent when `obj` comes into being mid game so the queue can
+// give it the listeners of its kind, see GameEventQueue::define_kind
+#[derive(Serialize, Deserialize)]
+pub struct SpawnData {
+    pub obj: String,
+    pub kind: String
+}
+
+impl SpawnData {
+    pub fn event(self) -> GameEvent {
+        GameEvent {
+            ev_type: "game.spawn".to_string(),
+            data: serde_json::to_string(&self).unwrap()
+        }
+    }
+}
+
+// game.open_door and game.close_door, sent when `actor` tries the door at
+// x, y of level `map`
+#[derive(Serialize, Deserialize)]
+pub struct DoorData {
+    pub actor: String,
+    pub x: u16,
+    pub y: u16,
+    pub map: u16
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum DamageKind {
+    Physical,
+    Fire,
+    Poison,
+    Magic
+}
+
+impl DamageKind {
+    // how a death from this kind of damage is described
+    pub fn killed(&self) -> &'static str {
+        match self {
+            DamageKind::Physical => "killed",
+            DamageKind::Fire => "burned to death",
+            DamageKind::Poison => "poisoned",
+            DamageKind::Magic => "blasted"
+        }
+    }
+}
+
+// game.damage, taking `amount` health from `target`. `source` is whatever
+// dealt it, an object or something like "lava".
+#[derive(Serialize, Deserialize)]
+pub struct DamageData {
+    pub target: String,
+    pub source: String,
+    pub amount: u16,
+    pub kind: DamageKind,
+    // what a death from it is put down to, if not the source
+    #[serde(default)]
+    pub cause: Option<String>
+}
+
+// game.heal, giving health back to `target`, up to its maximum
+#[derive(Serialize, Deserialize)]
+pub struct HealData {
+    pub target: String,
+    pub source: String,
+    pub amount: u16,
+    // heals without a message, e.g. regeneration which would fill the log
+    #[serde(default)]
+    pub quiet: bool
+}
+
+// game.death, sent while the dead object still exists, before it's removed
+#[derive(Serialize, Deserialize)]
+pub struct DeathData {
+    pub target: String,
+    pub source: String,
+    // e.g. "killed by the player"
+    pub cause: String
+}
+
+// game.pick_up and game.drop, `actor` taking `item` from the floor or putting
+// it back down where they stand
+#[derive(Serialize, Deserialize)]
+pub struct ItemData {
+    pub actor: String,
+    pub item: String
+}
+
+impl ItemData {
+    pub fn event(self, ev_type: &str) -> GameEvent {
+        GameEvent {
+            ev_type: ev_type.to_string(),
+            data: serde_json::to_string(&self).unwrap()
+        }
+    }
+}
+
+// game.effect, sent when `user` uses a Consumable `item`. Only the effect
+// script registered as `effect` acts on it.
+#[derive(Serialize, Deserialize)]
+pub struct EffectData {
+    pub effect: String,
+    pub user: String,
+    pub item: String,
+    pub power: u16
+}
+
+impl EffectData {
+    pub fn event(self) -> GameEvent {
+        GameEvent {
+            ev_type: "game.effect".to_string(),
+            data: serde_json::to_string(&self).unwrap()
+        }
+    }
+}
+
+impl HealData {
+    pub fn event(self) -> GameEvent {
+        GameEvent {
+            ev_type: "game.heal".to_string(),
+            data: serde_json::to_string(&self).unwrap()
+        }
+    }
+}
+
+impl DamageData {
+    pub fn event(self) -> GameEvent {
+        GameEvent {
+            ev_type: "game.damage".to_string(),
+            data: serde_json::to_string(&self).unwrap()
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct TickData {
     pub tick: u16
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct TurnData {
+    pub turn: u32
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MessageData {
+    pub text: String
+}
+
+impl MessageData {
+    // convenience for scripts which want to write to the message log
+    pub fn event(text: String) -> GameEvent {
+        GameEvent {
+            ev_type: "game.message".to_string(),
+            data: serde_json::to_string(&MessageData { text }).unwrap()
+        }
+    }
+}
+
 #[derive(Clone)]
 // data is a JSON-encoded representation
 pub struct GameEvent {
@@ -60,7 +243,9 @@ pub struct GameEventQueue {
     // hash id of listener against listener function
     listeners: HashMap<u16, Listener>,
     // hash event types against listener ids
-    listener_evs: HashMap<String, HashSet<u16>>
+    listener_evs: HashMap<String, HashSet<u16>>,
+    // the listeners each kind of object gets, by kind
+    kinds: HashMap<String, Vec<Listener>>
 }
 
 impl GameEventQueue {
@@ -69,7 +254,21 @@ impl GameEventQueue {
         Self {
             next_id: 0,
             listeners: HashMap::new(),
-            listener_evs: HashMap::new()
+            listener_evs: HashMap::new(),
+            kinds: HashMap::new()
+        }
+    }
+
+    /// Listeners every object of `kind` gets, with their object_id set to
+    /// the object's. Objects made mid game get them through game.spawn.
+    pub fn define_kind(&mut self, kind: &str, listeners: Vec<Listener>) {
+        self.kinds.insert(kind.to_string(), listeners);
+    }
+
+    pub fn attach_kind(&mut self, kind: &str, obj: &str) {
+        let listeners = self.kinds.get(kind).cloned().unwrap_or_default();
+        for listener in listeners {
+            self.attach_listener(Listener { object_id: obj.to_string(), ..listener });
         }
     }
 
@@ -86,7 +285,6 @@ impl GameEventQueue {
                 None => panic!("Listener for this ev type doesn't exist."),
                 Some(o) => {
                     o.insert(self.next_id);
-                    ()
                 }
             }
         }
@@ -98,20 +296,23 @@ impl GameEventQueue {
     pub fn trigger_listeners(&mut self, game: &mut GameManager, initial_ev: GameEvent) {
         let mut evs = vec![initial_ev];
 
-        while evs.len() > 0 {
-
-            let ev = evs.pop().unwrap();
+        while let Some(ev) = evs.pop() {
 
             if ev.ev_type == "game.remove_object" {
                 game.remove_object(ev.data.as_str());
             }
+            if ev.ev_type == "game.spawn" {
+                let data: SpawnData = serde_json::from_str(ev.data.as_str()).unwrap();
+                self.attach_kind(&data.kind, &data.obj);
+            }
 
-            let to_trigger: &mut HashSet<u16>;
             let type_of = ev.ev_type.as_str();
-            match self.listener_evs.get_mut(type_of) {
-                None => return,
-                Some(o) => {to_trigger = o} 
-            }
+            // events nobody listens for are dropped, but the rest of the
+            // chain still has to be delivered
+            let to_trigger: &mut HashSet<u16> = match self.listener_evs.get_mut(type_of) {
+                None => continue,
+                Some(o) => o
+            };
             for id in to_trigger.iter() {
                 let mut callbacks : Vec<GameEvent> = match self.listeners.get(id) {
                     None => panic!("Listeners by type and by index out of sync."),
@@ -0,0 +1,319 @@
+use std::{fs, str::FromStr, time::{SystemTime, UNIX_EPOCH}};
+
+use anyhow::{anyhow, Context, Result};
+use ratatui::{
+    backend::TestBackend,
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+    Terminal
+};
+
+use crate::components::{Glyph, TileMap, WorldPosition, map_object};
+use crate::game::GameManager;
+use crate::rterm;
+
+// Dumps of the screen or map, for sharing bug reports and dungeons.
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    Text,
+    Ansi,
+    Html
+}
+
+impl DumpFormat {
+    pub const ALL: [DumpFormat; 3] = [DumpFormat::Text, DumpFormat::Ansi, DumpFormat::Html];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DumpFormat::Text => "txt",
+            DumpFormat::Ansi => "ansi",
+            DumpFormat::Html => "html"
+        }
+    }
+}
+
+impl FromStr for DumpFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" | "txt" => Ok(DumpFormat::Text),
+            "ansi" => Ok(DumpFormat::Ansi),
+            "html" => Ok(DumpFormat::Html),
+            other => Err(anyhow!("unknown dump format '{}', expected text, ansi or html", other))
+        }
+    }
+}
+
+/// Render the whole screen as it would appear in a terminal of `size`.
+pub fn screen_buffer(game: &mut GameManager, size: (u16, u16)) -> Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(size.0, size.1))?;
+    terminal.draw(rterm::assemble_render(game))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// The player's level with every glyph on it, ignoring the camera and HUD.
+pub fn map_buffer(game: &mut GameManager) -> Result<Buffer> {
+    let level = game.get_component_data::<WorldPosition>("WorldPosition", "player").map_or(0, |p| p.map);
+    let map: TileMap = game.get_component_data("TileMap", &map_object(level))
+        .context("there is no map to dump")?;
+
+    let mut buffer = Buffer::empty(map.to_rect());
+    let size = map.get_size();
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            buffer.get_mut(x, y).set_char(map.tile_at((x, y)).glyph());
+        }
+    }
+
+    let glyphs: Vec<(String, Glyph)> = game.get_components_by_type("Glyph")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|comp| (comp.obj_id.clone(), comp.extract_data()))
+        .collect();
+
+    for (obj, glyph) in rterm::under_first(game, glyphs) {
+        let pos: WorldPosition = match game.get_component_data("WorldPosition", &obj) {
+            None => continue,
+            Some(p) => p
+        };
+        if pos.map == level && pos.x < size.0 && pos.y < size.1 {
+            buffer.get_mut(pos.x, pos.y).set_char(glyph.glyph);
+        }
+    }
+
+    Ok(buffer)
+}
+
+pub fn format_buffer(buffer: &Buffer, format: DumpFormat) -> String {
+    match format {
+        DumpFormat::Text => to_text(buffer),
+        DumpFormat::Ansi => to_ansi(buffer),
+        DumpFormat::Html => to_html(buffer)
+    }
+}
+
+/// Write the buffer in every format as `<stem>.txt`, `<stem>.ansi` and
+/// `<stem>.html`, returning the paths written.
+pub fn write_dumps(buffer: &Buffer, stem: &str) -> Result<Vec<String>> {
+    let mut paths = vec![];
+    for format in DumpFormat::ALL {
+        let path = format!("{}.{}", stem, format.extension());
+        fs::write(&path, format_buffer(buffer, format))
+            .with_context(|| format!("failed to write {}", path))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// A filename stem which won't collide with earlier dumps.
+pub fn timestamped_stem(prefix: &str) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    format!("{}-{}", prefix, millis)
+}
+
+fn rows(buffer: &Buffer) -> Vec<&[Cell]> {
+    let width = usize::from(buffer.area.width).max(1);
+    buffer.content().chunks(width).collect()
+}
+
+fn to_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let line: String = row.iter().map(|c| c.symbol.as_str()).collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let mut style: Option<(Color, Color, Modifier)> = None;
+        for cell in row {
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                out.push_str("\x1b[0");
+                if let Some(code) = ansi_color(cell.fg, false) {
+                    out.push(';');
+                    out.push_str(&code);
+                }
+                if let Some(code) = ansi_color(cell.bg, true) {
+                    out.push(';');
+                    out.push_str(&code);
+                }
+                if cell.modifier.contains(Modifier::BOLD) {
+                    out.push_str(";1");
+                }
+                out.push('m');
+                style = Some(cell_style);
+            }
+            out.push_str(&cell.symbol);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+fn ansi_color(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+    let base = |n: u8| Some((n + offset).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => base(30),
+        Color::Red => base(31),
+        Color::Green => base(32),
+        Color::Yellow => base(33),
+        Color::Blue => base(34),
+        Color::Magenta => base(35),
+        Color::Cyan => base(36),
+        Color::Gray => base(37),
+        Color::DarkGray => base(90),
+        Color::LightRed => base(91),
+        Color::LightGreen => base(92),
+        Color::LightYellow => base(93),
+        Color::LightBlue => base(94),
+        Color::LightMagenta => base(95),
+        Color::LightCyan => base(96),
+        Color::White => base(97),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", 38 + offset, r, g, b)),
+        Color::Indexed(i) => Some(format!("{};5;{}", 38 + offset, i))
+    }
+}
+
+fn to_html(buffer: &Buffer) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>Rusty Rogue</title>\n",
+        "<style>body { background: #000; color: #ccc; } ",
+        "pre { font-family: monospace; line-height: 1.1; }</style>\n",
+        "</head>\n<body>\n<pre>"
+    ));
+
+    for row in rows(buffer) {
+        let mut open: Option<(Color, Color, Modifier)> = None;
+        for cell in row {
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if open != Some(cell_style) {
+                if open.is_some() {
+                    out.push_str("</span>");
+                }
+                out.push_str(&format!("<span style=\"{}\">", css_style(cell)));
+                open = Some(cell_style);
+            }
+            for ch in cell.symbol.chars() {
+                match ch {
+                    '&' => out.push_str("&amp;"),
+                    '<' => out.push_str("&lt;"),
+                    '>' => out.push_str("&gt;"),
+                    c => out.push(c)
+                }
+            }
+        }
+        if open.is_some() {
+            out.push_str("</span>");
+        }
+        out.push('\n');
+    }
+
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+fn css_style(cell: &Cell) -> String {
+    let mut css = vec![];
+    if let Some(fg) = css_color(cell.fg) {
+        css.push(format!("color: {}", fg));
+    }
+    if let Some(bg) = css_color(cell.bg) {
+        css.push(format!("background: {}", bg));
+    }
+    if cell.modifier.contains(Modifier::BOLD) {
+        css.push("font-weight: bold".to_string());
+    }
+    css.join("; ")
+}
+
+// the xterm default palette
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255)
+];
+
+fn css_color(color: Color) -> Option<String> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Indexed(i) => i
+    };
+
+    let (r, g, b) = match index {
+        0..=15 => PALETTE[usize::from(index)],
+        16..=231 => {
+            let i = index - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        },
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, style::Style};
+
+    fn sample() -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(0, 0, "<@>", Style::default().fg(Color::Red));
+        buffer.set_string(0, 1, "&", Style::default());
+        buffer
+    }
+
+    #[test]
+    fn text_dump_trims_trailing_space() {
+        assert_eq!(format_buffer(&sample(), DumpFormat::Text), "<@>\n&\n");
+    }
+
+    #[test]
+    fn ansi_dump_colours_runs_of_cells() {
+        assert_eq!(
+            format_buffer(&sample(), DumpFormat::Ansi),
+            "\x1b[0;31m<@>\x1b[0m \x1b[0m\n\x1b[0m&   \x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn html_dump_escapes_and_styles() {
+        let html = format_buffer(&sample(), DumpFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<span style=\"color: #cd0000\">&lt;@&gt;</span><span style=\"\"> </span>\n"));
+        assert!(html.contains("<span style=\"\">&amp;   </span>\n"));
+    }
+}
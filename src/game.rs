@@ -2,8 +2,9 @@ use std::collections::{HashMap, HashSet};
 
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use crate::components::{Component, IsComponent};
-use crate::events::GameEvent;
+use rand::{SeedableRng, rngs::StdRng};
+use crate::components::{Component, IsComponent, WorldPosition, GameRng};
+use crate::spatial::SpatialIndex;
 
 // all event queues are stored by *object*, which seems wrong
 pub struct GameManager {
@@ -11,7 +12,9 @@ pub struct GameManager {
     // event_queue : GameEventQueue,
     components : HashMap<u16, Component>,
     components_by_type : HashMap<String, HashSet<u16>>,
-    components_by_obj : HashMap<String, HashSet<u16>>
+    components_by_obj : HashMap<String, HashSet<u16>>,
+    // follows WorldPosition components as they're added, set and removed
+    positions : SpatialIndex
 }
 
 impl GameManager {
@@ -21,7 +24,8 @@ impl GameManager {
             // event_queue: GameEventQueue::new(),
             components: HashMap::new(),
             components_by_obj: HashMap::new(),
-            components_by_type: HashMap::new()
+            components_by_type: HashMap::new(),
+            positions: SpatialIndex::new()
         };
     }
 
@@ -49,6 +53,7 @@ impl GameManager {
             Some(o) => o
         };
         set.insert(self.next_id);
+        self.index_position(self.next_id);
 
         self.next_id += 1;
         self.next_id - 1
@@ -60,77 +65,28 @@ impl GameManager {
         self.add_component(comp)
     }
 
-    pub fn get_component_mut(&mut self, id: u16) -> Option<&mut Component> {
-        self.components.get_mut(&id)
-    }
-
     pub fn get_component(&self, id: u16) -> Option<&Component> {
         self.components.get(&id)
     }
 
-    pub fn get_components_by_obj_mut(&mut self, obj: &str) -> Option<Vec<&mut Component>> {
-        match self.components_by_obj.get(obj) {
-            None => Option::None,
-            Some(id_set) => {
-
-                let ids = Vec::from_iter(id_set);
-
-                let mut components: Vec<*mut Component> = vec![];
-                let comp_link = &mut self.components;
-                for id in ids {
-                    {
-                    // we want this to panic on fail because it means
-                    // the indexes have gone out of sync
-                    let comp: *mut Component = comp_link.get_mut(id).unwrap();
-                    components.push(comp);
-                    }
-                }
-                // hack to allow mapping hashset to hashmap keys
-                // very bad and literally unsafe, high priority to refactor
-                let components = components.into_iter().map(|ptr| unsafe { &mut *ptr } ).collect();
-                Option::Some( components )
-            }
-        }
+    // Components are only lent out to be read. Changes go through
+    // set_component_data, so nothing can move without the index knowing.
+    pub fn get_components_by_obj(&self, obj: &str) -> Option<Vec<&Component>> {
+        let ids = self.components_by_obj.get(obj)?;
+        // we want this to panic on fail because it means
+        // the indexes have gone out of sync
+        Some(ids.iter().map(|id| &self.components[id]).collect())
     }
 
-    pub fn get_components_by_type_mut(&mut self, c_type: &str) -> Option<Vec<&mut Component>> {
-        match self.components_by_type.get(c_type) {
-            None => Option::None,
-            Some(ids) => {
-                let mut components: Vec<*mut Component> = vec![];
-                let comp_link = &mut self.components;
-                for id in ids {
-                    // we want this to panic on fail because it means
-                    // the indexes have gone out of sync
-                    components.push(comp_link.get_mut(id).unwrap());
-                }
-                // very bad and literally unsafe, high priority to refactor
-                let components = components.into_iter().map(|ptr| unsafe { &mut *ptr } ).collect();
-
-                Option::Some(components)
-            }
-        }
+    pub fn get_components_by_type(&self, c_type: &str) -> Option<Vec<&Component>> {
+        let ids = self.components_by_type.get(c_type)?;
+        Some(ids.iter().map(|id| &self.components[id]).collect())
     }
 
-    pub fn get_components(&mut self, c_type: &str, obj: &str) -> Option<Vec<&mut Component>> {
-        let by_type = self.components_by_type.get(c_type);
-        let by_obj = self.components_by_obj.get(obj);
-        if by_type.is_none() || by_obj.is_none() {
-            Option::None
-        }
-        else {
-            let by_type = by_type.unwrap();
-            let by_obj = by_obj.unwrap();
-            let mut union: Vec<*mut Component> = vec![];
-            for id in by_type.intersection(by_obj) {
-                // we want this to panic on fail because it means
-                // the indexes have gone out of sync
-                union.push(self.components.get_mut(id).unwrap())
-            }
-            // very bad and literally unsafe, high priority to refactor
-            let union: Vec<&mut Component> = union.into_iter().map(|ptr| unsafe { &mut *ptr } ).collect();
-            Option::Some(union)
-        }
+    pub fn get_components(&self, c_type: &str, obj: &str) -> Option<Vec<&Component>> {
+        let by_type = self.components_by_type.get(c_type)?;
+        let by_obj = self.components_by_obj.get(obj)?;
+        Some(by_type.intersection(by_obj).map(|id| &self.components[id]).collect())
     }
 
     pub fn get_component_data<T>(&mut self, c_type: &str, obj: &str) -> Option<T> 
@@ -139,21 +95,98 @@ impl GameManager {
         let comp_options = self.get_components(c_type, obj);
         match comp_options {
             None => return Option::None,
-            Some(mut c) => {
+            // the object may have components, just not this type
+            Some(c) if c.is_empty() => return Option::None,
+            Some(c) => {
                 Option::Some(c[0].extract_data())
             }
         }
     }
 
-    pub fn remove_object(&mut self, obj_name : &str) {
-        let components = self.get_components_by_obj_mut(obj_name).unwrap();
-        let mut c_to_remove: HashSet<u16> = HashSet::new();
+    // overwrite the object's component of this type, adding it if missing
+    pub fn set_component_data(&mut self, datum: &(impl IsComponent + Serialize), obj_id: &str) {
+        let c_type = datum.get_type_name();
+        match self.get_components(&c_type, obj_id) {
+            Some(c) if !c.is_empty() => {
+                let id = c[0].id;
+                self.components.get_mut(&id).unwrap().set_data(datum);
+                self.index_position(id);
+            },
+            _ => {
+                self.add_component_from_data(datum, obj_id);
+            }
+        }
+    }
+
+    // refiles the component's object if it's a position
+    fn index_position(&mut self, id: u16) {
+        let comp = &self.components[&id];
+        if comp.c_type == "WorldPosition" {
+            let pos: WorldPosition = comp.extract_data();
+            self.positions.place(&comp.obj_id, pos.map, (pos.x, pos.y));
+        }
+    }
 
-        for comp in components {
-            c_to_remove.insert(comp.id);
+    /// The objects standing on a tile of a level.
+    pub fn objects_at(&self, map: u16, pos: (u16, u16)) -> &[String] {
+        self.positions.at(map, pos)
+    }
+
+    // the raw JSON of a component, for code which inspects fields by name
+    pub fn get_component_value(&mut self, c_type: &str, obj: &str) -> Option<serde_json::Value> {
+        match self.get_components(c_type, obj) {
+            Some(c) if !c.is_empty() => serde_json::from_str(c[0].data.as_str()).ok(),
+            _ => Option::None
+        }
+    }
+
+    /// A fresh rng for one action, from the GameRng on the "dungeon" object.
+    /// Each call gives a different one, but the sequence is fixed by the seed.
+    pub fn rng(&mut self) -> StdRng {
+        let mut state: GameRng = self.get_component_data("GameRng", "dungeon")
+            .unwrap_or(GameRng { seed: 0, draws: 0 });
+        state.draws += 1;
+        let rng = StdRng::seed_from_u64(state.seed ^ state.draws.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        self.set_component_data(&state, "dungeon");
+        rng
+    }
+
+    // drops the object's component of this type, if it has one
+    pub fn remove_component(&mut self, c_type: &str, obj_id: &str) {
+        let id = match self.get_components(c_type, obj_id) {
+            Some(c) if !c.is_empty() => c[0].id,
+            _ => return
+        };
+        self.components.remove(&id);
+        if let Some(set) = self.components_by_obj.get_mut(obj_id) {
+            set.remove(&id);
         }
+        if let Some(set) = self.components_by_type.get_mut(c_type) {
+            set.remove(&id);
+        }
+        if c_type == "WorldPosition" {
+            self.positions.remove(obj_id);
+        }
+    }
+
+    /// Gives `to` a copy of each of `from`'s components.
+    pub fn copy_object(&mut self, from: &str, to: &str) {
+        let copies: Vec<Component> = self.get_components_by_obj(from).unwrap_or_default().into_iter()
+            .map(|c| Component { id: 0, obj_id: to.to_string(), data: c.data.clone(), c_type: c.c_type.clone() })
+            .collect();
+        for copy in copies {
+            self.add_component(copy);
+        }
+    }
+
+    pub fn remove_object(&mut self, obj_name : &str) {
+        let c_to_remove: HashSet<u16> = self.get_components_by_obj(obj_name).unwrap()
+            .iter()
+            .map(|comp| comp.id)
+            .collect();
 
         self.components_by_obj.remove(obj_name);
+        self.positions.remove(obj_name);
 
         for cid in c_to_remove {
             self.components.remove(&cid);
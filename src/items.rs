@@ -0,0 +1,219 @@
+use rand::Rng;
+
+use crate::components::{Carryable, Consumable, EquipSlot, Equippable, Equipment, Ranged, Glyph, Inventory, Item, WorldPosition, StatusEffect, StatusEffects, EffectKind};
+use crate::game::GameManager;
+
+// The things which can be found lying around the dungeon.
+
+pub struct ItemTemplate {
+    pub name: &'static str,
+    pub glyph: char,
+    pub weight: u16,
+    pub stacks: bool,
+    pub description: &'static str,
+    pub equip: Option<Equippable>,
+    // (script, power, verb), see Consumable
+    pub consume: Option<(&'static str, u16, &'static str)>,
+    // status effects given by using it, see the afflict script
+    pub grants: Option<StatusEffect>,
+    pub ranged: Option<Ranged>
+}
+
+// an item which afflicts whoever drinks it
+fn potion(name: &'static str, description: &'static str, kind: EffectKind, turns: u16, potency: u16) -> ItemTemplate {
+    ItemTemplate {
+        name, glyph: '!', weight: 1, stacks: true, description,
+        equip: None,
+        consume: Some(("afflict", 0, "drinks")),
+        grants: Some(StatusEffect { kind, turns, potency }),
+        ranged: None
+    }
+}
+
+pub fn item_templates() -> Vec<ItemTemplate> {
+    vec![
+        ItemTemplate {
+            name: "dagger", glyph: ')', weight: 2, stacks: false,
+            description: "A short, sharp blade.",
+            equip: Some(Equippable { damage: Some("1d8".to_string()), accuracy: 5, ..Equippable::new(EquipSlot::Weapon) }),
+            consume: None,
+            grants: None,
+            ranged: None
+        },
+        ItemTemplate {
+            name: "short bow", glyph: '}', weight: 3, stacks: false,
+            description: "Fired with f while wielded, up to 8 tiles.",
+            equip: Some(Equippable { damage: Some("1d6".to_string()), ..Equippable::new(EquipSlot::Weapon) }),
+            consume: None,
+            grants: None,
+            ranged: Some(Ranged { range: 8, thrown: false })
+        },
+        ItemTemplate {
+            name: "throwing knife", glyph: ')', weight: 1, stacks: true,
+            description: "Thrown with t, up to 6 tiles.",
+            equip: Some(Equippable { damage: Some("1d4".to_string()), accuracy: 5, ..Equippable::new(EquipSlot::Weapon) }),
+            consume: None,
+            grants: None,
+            ranged: Some(Ranged { range: 6, thrown: true })
+        },
+        ItemTemplate {
+            name: "leather armour", glyph: '[', weight: 10, stacks: false,
+            description: "Stiff, but better than nothing.",
+            equip: Some(Equippable { defense: 2, evasion: -2, ..Equippable::new(EquipSlot::Armour) }),
+            consume: None,
+            grants: None,
+            ranged: None
+        },
+        ItemTemplate {
+            name: "copper ring", glyph: '=', weight: 1, stacks: false,
+            description: "A plain band of copper.",
+            equip: Some(Equippable { max_health: 5, ..Equippable::new(EquipSlot::Ring) }),
+            consume: None,
+            grants: None,
+            ranged: None
+        },
+        ItemTemplate {
+            name: "healing potion", glyph: '!', weight: 1, stacks: true,
+            description: "A small flask of something red. It heals 8.",
+            equip: None,
+            consume: Some(("healing", 8, "drinks")),
+            grants: None,
+            ranged: None
+        },
+        ItemTemplate {
+            name: "scroll of teleportation", glyph: '?', weight: 1, stacks: true,
+            description: "Reading it takes you elsewhere on the level.",
+            equip: None,
+            consume: Some(("teleport", 0, "reads")),
+            grants: None,
+            ranged: None
+        },
+        ItemTemplate {
+            name: "scroll of magic mapping", glyph: '?', weight: 1, stacks: true,
+            description: "Reading it shows you the whole level.",
+            equip: None,
+            consume: Some(("magic_mapping", 0, "reads")),
+            grants: None,
+            ranged: None
+        },
+        ItemTemplate {
+            name: "scroll of fear", glyph: '?', weight: 1, stacks: true,
+            description: "Monsters in sight won't attack for 10 turns.",
+            equip: None,
+            consume: Some(("fear", 10, "reads")),
+            grants: None,
+            ranged: None
+        },
+        potion("potion of regeneration", "It heals 1 a turn for 20 turns.", EffectKind::Regeneration, 20, 1),
+        potion("potion of speed", "It gives two actions a turn for 15 turns.", EffectKind::Haste, 15, 0),
+        potion("murky potion", "It smells off. Poisonous, 2 a turn for 5 turns.", EffectKind::Poison, 5, 2),
+        potion("potion of confusion", "It makes the head spin for 10 turns.", EffectKind::Confusion, 10, 0),
+        potion("potion of blindness", "It darkens the eyes for 10 turns.", EffectKind::Blindness, 10, 0)
+    ]
+}
+
+// how many items a generated level gets besides those its vaults ask for
+pub const ITEMS_PER_LEVEL: usize = 3;
+
+/// Puts `template` on the map as `obj_id`.
+pub fn spawn_item(game: &mut GameManager, template: &ItemTemplate, obj_id: &str, pos: WorldPosition) {
+    game.add_component_from_data(&Item {
+        name: template.name.to_string(),
+        description: template.description.to_string()
+    }, obj_id);
+    game.add_component_from_data(&Carryable { weight: template.weight, count: 1, stacks: template.stacks }, obj_id);
+    game.add_component_from_data(&Glyph { glyph: template.glyph }, obj_id);
+    if let Some(equip) = &template.equip {
+        game.add_component_from_data(equip, obj_id);
+    }
+    if let Some((script, power, verb)) = template.consume {
+        game.add_component_from_data(&Consumable { script: script.to_string(), power, verb: verb.to_string() }, obj_id);
+    }
+    if let Some(ranged) = template.ranged {
+        game.add_component_from_data(&ranged, obj_id);
+    }
+    if let Some(effect) = template.grants {
+        game.add_component_from_data(&StatusEffects { effects: vec![effect] }, obj_id);
+    }
+    game.add_component_from_data(&pos, obj_id);
+}
+
+/// A randomly chosen item on each of the spots of level `map`.
+pub fn place_items(game: &mut GameManager, map: u16, spots: &[(u16, u16)]) {
+    let mut rng = game.rng();
+    let templates = item_templates();
+    for (i, spot) in spots.iter().enumerate() {
+        let template = &templates[rng.gen_range(0..templates.len())];
+        let pos = WorldPosition { x: spot.0, y: spot.1, map };
+        spawn_item(game, template, &format!("item{}_{}", map, i), pos);
+    }
+}
+
+/// The spots on a generated level to put items: those its vaults set aside,
+/// then a few of the monster spawns nearest the start.
+pub fn item_spots(item_spawns: &[(u16, u16)], spawns: &[(u16, u16)]) -> Vec<(u16, u16)> {
+    // the furthest spawn is the monster's lair
+    let mut spots = item_spawns.to_vec();
+    spots.extend(spawns.iter().skip(1).rev().take(ITEMS_PER_LEVEL));
+    spots
+}
+
+/// The item's name, with how many there are if it's a stack.
+pub fn item_label(game: &mut GameManager, obj: &str) -> String {
+    let name = game.get_component_data::<Item>("Item", obj).map_or(obj.to_string(), |i| i.name);
+    match game.get_component_data::<Carryable>("Carryable", obj) {
+        Some(c) if c.count > 1 => format!("{} (x{})", name, c.count),
+        _ => name
+    }
+}
+
+/// Takes one off the top of a stack of `item` as an object of its own,
+/// returning its id. Anything else is the one item already.
+pub fn split_one(game: &mut GameManager, item: &str) -> String {
+    let mut carryable = match game.get_component_data::<Carryable>("Carryable", item) {
+        Some(c) if c.count > 1 => c,
+        _ => return item.to_string()
+    };
+    carryable.count -= 1;
+    game.set_component_data(&carryable, item);
+
+    let one = (1..).map(|n| format!("{}.{}", item, n))
+        .find(|id| game.get_components_by_obj(id).is_none_or(|c| c.is_empty()))
+        .unwrap();
+    game.copy_object(item, &one);
+    game.set_component_data(&Carryable { count: 1, ..carryable }, &one);
+    one
+}
+
+/// item_label, plus the slot it's in if `actor` has it equipped.
+pub fn carried_label(game: &mut GameManager, actor: &str, obj: &str) -> String {
+    let label = item_label(game, obj);
+    let equipment: Equipment = game.get_component_data("Equipment", actor).unwrap_or_default();
+    match equipment.slot_of(obj) {
+        None => label,
+        Some(slot) => format!("{} ({})", label, slot.name())
+    }
+}
+
+/// What everything in `actor`'s Inventory weighs together.
+pub fn carried_weight(game: &mut GameManager, actor: &str) -> u16 {
+    let inventory: Inventory = game.get_component_data("Inventory", actor).unwrap_or_default();
+    inventory.items.iter()
+        .filter_map(|obj| game.get_component_data::<Carryable>("Carryable", obj))
+        .fold(0, |total, c| total.saturating_add(c.total_weight()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::Dice;
+
+    #[test]
+    fn every_weapon_has_real_dice() {
+        for template in item_templates() {
+            if let Some(damage) = template.equip.and_then(|e| e.damage) {
+                assert!(damage.parse::<Dice>().is_ok(), "the {} does '{}'", template.name, damage);
+            }
+        }
+    }
+}
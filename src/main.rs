@@ -1,24 +1,36 @@
-use anyhow::{Context, Result, Error};
+use anyhow::{anyhow, Context, Result};
 
 use game::GameManager;
-use events::{GameEvent, Listener, GameEventQueue, InputData, TickData};
-use components::{WorldPosition, Glyph, TileMap, TileType, Health, TextBox, ScreenPosition, Monitor};
-use scripts::{player_move, on_hit, update_health};
+use export::DumpFormat;
+use mapfile::MapFile;
+use mapgen::{generate_level, GENERATOR_NAMES, StyleOptions, Pockets, CorridorStyle, Prefab, builtin_prefabs, load_prefabs, prefabs_to_text};
+use events::{GameEvent, Listener, GameEventQueue, InputData, TickData, TurnData, MessageData};
+use components::{WorldPosition, Glyph, Health, GaugeBox, ScreenPosition, Monitor, MonitorKind, MessageLog, Status, GameOver, Key, Keys, Kills, Inventory, InputContext, Equipment, Explored, Levels, CombatStats, GameRng, map_object};
+use scripts::{player_move, on_hit, update_monitor, log_message, update_status, show_health_bar, use_door, use_stairs, take_damage, heal, player_death, monster_attack, count_kill, item_keys, pick_up, drop_item, open_inventory, inventory_keys, equip_item, explore, use_item, tick_status, aim, target_keys, fire, effect_scripts, attach_effects};
 
-use ratatui::{backend::CrosstermBackend, widgets::{Paragraph, canvas::Map}, Terminal, layout::Rect};
+use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
-    io::{self, Stdout},
-    time::Duration, collections::HashMap, str::FromStr,
-};
-use crossterm::{
-    event::{self, Event, KeyCode},
+    io::Stdout,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
+use crossterm::event::KeyCode;
 
 mod rterm;
 mod game;
 mod components;
 mod events;
 mod scripts;
+mod export;
+mod mapgen;
+mod mapfile;
+mod spatial;
+mod dice;
+mod morgue;
+mod items;
+mod stats;
+mod targeting;
+mod monsters;
 
 /// This is a bare minimum example. There are many approaches to running an application loop, so
 /// this is not meant to be prescriptive. It is only meant to demonstrate the basic setup and
@@ -29,64 +41,249 @@ mod scripts;
 /// events or update the application state. It just draws a greeting and exits when the user
 /// presses 'q'.
 fn main() -> Result<()> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let seed = match take_option(&mut args, "--seed")? {
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        Some(s) => s.parse::<u64>().context("--seed needs a number")?
+    };
+    let map_style = take_option(&mut args, "--map")?.unwrap_or("rooms".to_string());
+    let pockets = match take_option(&mut args, "--pockets")? {
+        None => Pockets::default(),
+        Some(p) => p.parse::<Pockets>()?
+    };
+    let corridors = match take_option(&mut args, "--corridors")? {
+        None => CorridorStyle::default(),
+        Some(c) => c.parse::<CorridorStyle>()?
+    };
+    let vaults = match take_option(&mut args, "--vaults")? {
+        None => builtin_prefabs(),
+        Some(path) => load_prefabs(std::path::Path::new(&path))?
+    };
+    let map_file = match take_option(&mut args, "--map-file")? {
+        None => None,
+        Some(path) => Some(MapFile::load(std::path::Path::new(&path))?)
+    };
+    let morgue_dir = take_option(&mut args, "--morgue-dir")?.unwrap_or("morgue".to_string());
+    let mut options = GameOptions { seed, map_style, style: StyleOptions { pockets, corridors }, vaults, map_file, morgue_dir: PathBuf::from(morgue_dir) };
+
+    if args.first().map(String::as_str) == Some("dump") {
+        return dump_command(&args[1..], &options);
+    }
+
     let mut terminal = rterm::setup_terminal().context("setup failed")?;
-    
-    let player_pos = WorldPosition {
-        x: 1,
-        y: 1,
-        map: 0
+    // the terminal is restored even if the game fails
+    let result = play(&mut terminal, &mut options);
+    rterm::restore_terminal(&mut terminal).context("restore terminal failed")?;
+    result
+}
+
+// Runs games until the player stops restarting them.
+fn play(terminal: &mut Terminal<CrosstermBackend<Stdout>>, options: &mut GameOptions) -> Result<()> {
+    loop {
+        let (mut game, mut eq) = new_game(options)?;
+        match run(terminal, &mut game, &mut eq, &options.morgue_dir).context("app loop failed")? {
+            Outcome::Quit => return Ok(()),
+            Outcome::Restart => options.seed = new_seed(options.seed)
+        }
+    }
+}
+
+// from the clock, but never the seed just played
+fn new_seed(old: u64) -> u64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    if now == old { now.wrapping_add(1) } else { now }
+}
+
+// How a game ended
+pub enum Outcome {
+    Quit,
+    Restart
+}
+
+// Everything needed to set up a new game
+pub struct GameOptions {
+    pub seed: u64,
+    pub map_style: String,
+    pub style: StyleOptions,
+    pub vaults: Vec<Prefab>,
+    // a hand written level, used instead of generating one
+    pub map_file: Option<MapFile>,
+    // where morgue files go when a game ends
+    pub morgue_dir: PathBuf
+}
+
+// Removes `name VALUE` from the arguments, returning the value.
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    match args.iter().position(|a| a == name) {
+        None => Ok(None),
+        Some(i) => {
+            let value = args.get(i + 1)
+                .ok_or(anyhow!("{} needs a value", name))?
+                .clone();
+            args.drain(i..=i + 1);
+            Ok(Some(value))
+        }
+    }
+}
+
+// rusty-rogue dump <screen|map> <text|ansi|html> [file]
+// Dumps the starting state without opening the game, to stdout by default.
+fn dump_command(args: &[String], options: &GameOptions) -> Result<()> {
+    let usage = "usage: rusty-rogue dump <screen|map> <text|ansi|html> [file]";
+    let (target, format) = match args {
+        [target, format, ..] => (target.as_str(), format.parse::<DumpFormat>()?),
+        _ => return Err(anyhow!(usage))
     };
 
-    let player_glyph = Glyph {
-        glyph: '@'
+    let (mut game, mut eq) = new_game(options)?;
+    // let the player look around and monitors fill in their widgets before
+    // anything is drawn
+    eq.trigger_listeners(&mut game, GameEvent {
+        ev_type: "game.start".to_string(),
+        data: "".to_string()
+    });
+    eq.trigger_listeners(&mut game, GameEvent {
+        ev_type: "game.tick".to_string(),
+        data: serde_json::to_string(&TickData { tick: 0 })?
+    });
+
+    let buffer = match target {
+        "screen" => {
+            let size = crossterm::terminal::size().unwrap_or((80, 24));
+            export::screen_buffer(&mut game, size)?
+        },
+        "map" => export::map_buffer(&mut game)?,
+        _ => return Err(anyhow!(usage))
     };
 
-    let enemy_pos = WorldPosition {
-        x: 10,
-        y: 10,
-        map: 0
+    let out = export::format_buffer(&buffer, format);
+    match args.get(2) {
+        None => print!("{}", out),
+        Some(path) => std::fs::write(path, out).with_context(|| format!("failed to write {}", path))?
+    }
+    Ok(())
+}
+
+fn new_game(options: &GameOptions) -> Result<(GameManager, GameEventQueue)> {
+    let seed = options.seed;
+    let map_style = options.map_style.as_str();
+    if !GENERATOR_NAMES.contains(&map_style) {
+        return Err(anyhow!("unknown map style '{}', expected one of {}", map_style, GENERATOR_NAMES.join(", ")));
+    }
+    let mut key_spots = vec![];
+    let item_spots;
+    let mut monster_spots = vec![];
+    let (map, start, lair) = match &options.map_file {
+        // map files place the player themselves and may leave out the enemy
+        Some(file) => {
+            let start = file.entity("player").ok_or(anyhow!("the map file has no player"))?;
+            key_spots = file.entities.iter().filter(|(name, _)| name == "key").map(|(_, pos)| *pos).collect();
+            item_spots = file.entities.iter().filter(|(name, _)| name == "item").map(|(_, pos)| *pos).collect();
+            (file.map.clone(), start, file.entity("enemy"))
+        },
+        None => {
+            let dungeon = generate_level(map_style, seed, 1, &options.vaults, &options.style)
+                .ok_or(anyhow!("unknown map style '{}'", map_style))?;
+            // as far from the player as possible
+            let lair = *dungeon.spawns.first().ok_or(anyhow!("the map has nowhere to put monsters"))?;
+            item_spots = items::item_spots(&dungeon.item_spawns, &dungeon.spawns);
+            monster_spots = dungeon.monster_spawns;
+            (dungeon.map, dungeon.start, Some(lair))
+        }
     };
 
-    let enemy_glyph = Glyph {
-        glyph: 'M'
+    let player_pos = WorldPosition {
+        x: start.0,
+        y: start.1,
+        map: 0
     };
 
-    let enemy_health = Health {
-        current_health: 10,
-        max_health: 10,
+    let player_glyph = Glyph {
+        glyph: '@'
     };
 
-    let enemy_health_box = TextBox {
-        value: String::from_str("?/?")?
+    let player_status = Status {
+        level: 1,
+        depth: 1,
+        turn: 0
     };
 
-    let enemy_health_monitor = Monitor {
-        to_monitor: vec![(String::from_str("enemy")?, String::from_str("Health")?)]
+    let enemy_health_box = GaugeBox {
+        label: String::from_str("Enemy ?/?")?,
+        ratio: 0.0
     };
 
+    let enemy_health_monitor = Monitor::new(
+        "Enemy {hp}/{max}",
+        MonitorKind::Gauge { value: "hp".to_string(), max: "max".to_string() }
+    )
+        .bind("hp", "enemy.Health.current_health")
+        .bind("max", "enemy.Health.max_health");
+
     let enemy_health_pos = ScreenPosition {
         x: 0,
         y: 0
     };
 
-    // ratatui handles text overflowing the buffer by truncating it - good
-    // translating from world -> camera space should therefore be sufficient
-    // for rendering to succeed even on large maps
-    let mut map = TileMap::new( (15,15) );
-    map.draw_rect(&Rect { x: 0, y: 0, width: 15, height: 15 }, TileType::WALL, false);
-    map.draw_rect(&Rect { x: 6, y: 6, width: 3, height: 3 }, TileType::WALL, true);
-
     let mut game = GameManager::new();
 
     game.add_component_from_data(&player_pos, "player");
     game.add_component_from_data(&player_glyph, "player");
-    game.add_component_from_data(&map, "map");
-    game.add_component_from_data(&enemy_glyph, "enemy");
-    game.add_component_from_data(&enemy_pos, "enemy");
-    game.add_component_from_data(&enemy_health, "enemy");
+    game.add_component_from_data(&player_status, "player");
+    game.add_component_from_data(&Health { current_health: 20, max_health: 20 }, "player");
+    game.add_component_from_data(&Keys { count: 0 }, "player");
+    game.add_component_from_data(&Kills::default(), "player");
+    game.add_component_from_data(&Inventory::default(), "player");
+    game.add_component_from_data(&Equipment::default(), "player");
+    let player_stats = CombatStats {
+        damage: "1d6+1".to_string(),
+        attack: 0,
+        defense: 1,
+        accuracy: 10,
+        evasion: 5
+    };
+    game.add_component_from_data(&player_stats, "player");
+    game.add_component_from_data(&Explored::new(map.get_size()), &map_object(0));
+    game.add_component_from_data(&map, &map_object(0));
+    // deeper levels are made as they're reached
+    let levels = Levels {
+        seed,
+        map_style: map_style.to_string(),
+        style: options.style,
+        vaults: prefabs_to_text(&options.vaults)
+    };
+    game.add_component_from_data(&levels, "dungeon");
+    game.add_component_from_data(&GameRng { seed, draws: 0 }, "dungeon");
+    if let Some(lair) = lair {
+        let enemy_pos = WorldPosition {
+            x: lair.0,
+            y: lair.1,
+            map: 0
+        };
+        monsters::spawn_monster(&mut game, &monsters::monster_for_depth(1), "enemy", enemy_pos);
+    }
+    // those in vaults get their listeners once the queue is made
+    let vault_monsters: Vec<GameEvent> = monster_spots.iter()
+        .map(|spot| monsters::place_monster(&mut game, 0, 1, *spot))
+        .collect();
+    for (i, spot) in key_spots.iter().enumerate() {
+        let key = format!("key{}", i);
+        game.add_component_from_data(&WorldPosition { x: spot.0, y: spot.1, map: 0 }, &key);
+        game.add_component_from_data(&Glyph { glyph: '-' }, &key);
+        game.add_component_from_data(&Key {}, &key);
+    }
+    items::place_items(&mut game, 0, &item_spots);
     game.add_component_from_data(&enemy_health_box, "enemy_hb");
     game.add_component_from_data(&enemy_health_monitor, "enemy_hb");
     game.add_component_from_data(&enemy_health_pos, "enemy_hb");
+    // the seed goes in the log so a dungeon can be shared or reported
+    let mut log = MessageLog::new(100);
+    log.push(format!("Dungeon seed: {}", seed));
+    game.add_component_from_data(&log, "log");
 
     let mut eq = GameEventQueue::new();
 
@@ -96,46 +293,188 @@ fn main() -> Result<()> {
         player_move
     );
 
-    let hit_listener = Listener::new(
+    let player_hit_listener = Listener::new(
         vec!["game.on_hit"],
-        "enemy",
+        "player",
         on_hit
     );
 
+    let death_listener = Listener::new(
+        vec!["game.death"],
+        "player",
+        player_death
+    );
+
     let update_listener = Listener::new(
         vec!["game.tick"],
         "enemy_hb",
-        update_health
+        update_monitor
+    );
+    
+    let message_listener = Listener::new(
+        vec!["game.message"],
+        "log",
+        log_message
+    );
+
+    let door_listener = Listener::new(
+        vec!["game.open_door", "game.close_door"],
+        "dungeon",
+        use_door
+    );
+
+    let stairs_listener = Listener::new(
+        vec!["input.key_press"],
+        "player",
+        use_stairs
+    );
+
+    let kill_listener = Listener::new(
+        vec!["game.death"],
+        "player",
+        count_kill
+    );
+
+    let item_key_listener = Listener::new(
+        vec!["input.key_press"],
+        "player",
+        item_keys
+    );
+
+    let pick_up_listener = Listener::new(
+        vec!["game.pick_up"],
+        "player",
+        pick_up
+    );
+
+    let drop_listener = Listener::new(
+        vec!["game.drop"],
+        "player",
+        drop_item
+    );
+
+    let open_inventory_listener = Listener::new(
+        vec!["input.key_press"],
+        "player",
+        open_inventory
+    );
+
+    let inventory_listener = Listener::new(
+        vec!["input.inventory"],
+        "player",
+        inventory_keys
+    );
+
+    let equip_listener = Listener::new(
+        vec!["game.equip", "game.unequip"],
+        "player",
+        equip_item
+    );
+
+    let explore_listener = Listener::new(
+        vec!["game.start", "game.turn"],
+        "player",
+        explore
+    );
+
+    let use_listener = Listener::new(
+        vec!["game.use_item", "game.use_up"],
+        "player",
+        use_item
+    );
+
+    let aim_listener = Listener::new(
+        vec!["input.key_press"],
+        "player",
+        aim
+    );
+
+    let target_listener = Listener::new(
+        vec!["input.target"],
+        "player",
+        target_keys
+    );
+
+    let fire_listener = Listener::new(
+        vec!["game.fire"],
+        "player",
+        fire
+    );
+
+    let status_listener = Listener::new(
+        vec!["game.turn"],
+        "player",
+        update_status
     );
     
+    // everything with health can be hurt and healed, and feels its status
+    // effects each turn
+    let living = |obj: &str| vec![
+        Listener::new(vec!["game.damage"], obj, take_damage),
+        Listener::new(vec!["game.heal"], obj, heal),
+        Listener::new(vec!["game.turn"], obj, tick_status)
+    ];
+    // monsters made on levels reached later get these too
+    let mut monster = living("");
+    monster.extend([
+        Listener::new(vec!["game.on_hit"], "", on_hit),
+        Listener::new(vec!["game.turn"], "", monster_attack),
+        Listener::new(vec!["game.on_hit", "game.target"], "", show_health_bar)
+    ]);
+    eq.define_kind("monster", monster);
+    if lair.is_some() {
+        eq.attach_kind("monster", "enemy");
+    }
+
     eq.attach_listener(input_listener);
-    eq.attach_listener(hit_listener);
+    for listener in living("player") {
+        eq.attach_listener(listener);
+    }
+    eq.attach_listener(player_hit_listener);
+    eq.attach_listener(death_listener);
+    eq.attach_listener(kill_listener);
     eq.attach_listener(update_listener);
+    eq.attach_listener(message_listener);
+    eq.attach_listener(door_listener);
+    eq.attach_listener(stairs_listener);
+    eq.attach_listener(item_key_listener);
+    eq.attach_listener(pick_up_listener);
+    eq.attach_listener(drop_listener);
+    eq.attach_listener(open_inventory_listener);
+    eq.attach_listener(inventory_listener);
+    eq.attach_listener(equip_listener);
+    eq.attach_listener(explore_listener);
+    eq.attach_listener(use_listener);
+    attach_effects(&mut eq, &effect_scripts());
+    eq.attach_listener(aim_listener);
+    eq.attach_listener(target_listener);
+    eq.attach_listener(fire_listener);
+    eq.attach_listener(status_listener);
+    for spawn in vault_monsters {
+        eq.trigger_listeners(&mut game, spawn);
+    }
 
-    run(&mut terminal, &mut game, &mut eq).context("app loop failed")?;
-    rterm::restore_terminal(&mut terminal).context("restore terminal failed")?;
-
-    Ok(())
+    Ok((game, eq))
 }
 
 // Render and poll terminal for keypress events
-pub fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, game : &mut GameManager, eq : &mut GameEventQueue) -> Result<()> {
+pub fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, game : &mut GameManager, eq : &mut GameEventQueue, morgue_dir: &Path) -> Result<Outcome> {
     let mut cur_tick: u16 = 0;
+    let mut cur_turn: u32 = 0;
     let start_ev = GameEvent {
         ev_type: "game.start".to_string(),
         data: "".to_string()
     };
     eq.trigger_listeners(game, start_ev);
+    eq.trigger_listeners(game, MessageData::event("Welcome to Rusty Rogue!".to_string()));
  
     loop {
+        if let Some(over) = game.get_component_data::<GameOver>("GameOver", "game") {
+            let note = write_morgue(game, &over, morgue_dir);
+            return game_over(terminal, &over, &note);
+        }
         terminal.draw(rterm::assemble_render(game))?;
         let key = rterm::poll()?;
-        let input_ev = GameEvent {
-            ev_type: "input.key_press".to_string(),
-            data: serde_json::to_string( &InputData {
-                key_code: key
-            })?
-        };
 
         let update_ev = GameEvent {
             ev_type: "game.tick".to_string(),
@@ -145,10 +484,163 @@ pub fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, game : &mut GameMa
         };
 
         eq.trigger_listeners(game, update_ev);
+        cur_tick = cur_tick.wrapping_add(1);
 
-        if key == KeyCode::Esc { break }
-        eq.trigger_listeners(game, input_ev);
-        
+        if key == KeyCode::Null { continue }
+
+        let context: InputContext = game.get_component_data("InputContext", "game").unwrap_or_default();
+        if context.name == "map" {
+            if key == KeyCode::Esc {
+                let over = morgue::end_game(game, "quit");
+                write_morgue(game, &over, morgue_dir);
+                return Ok(Outcome::Quit)
+            }
+
+            // dumps aren't turns, so they skip the rest of the loop
+            if key == KeyCode::Char('p') || key == KeyCode::Char('P') {
+                let size = terminal.size()?;
+                let (buffer, prefix) = if key == KeyCode::Char('p') {
+                    (export::screen_buffer(game, (size.width, size.height))?, "screen")
+                } else {
+                    (export::map_buffer(game)?, "map")
+                };
+                let stem = export::timestamped_stem(prefix);
+                let msg = match export::write_dumps(&buffer, &stem) {
+                    Ok(_) => format!("Saved {0}.txt, {0}.ansi and {0}.html", stem),
+                    Err(e) => format!("Dump failed: {}", e)
+                };
+                eq.trigger_listeners(game, MessageData::event(msg));
+                continue;
+            }
+        }
+        press_key(game, eq, key, &mut cur_turn)?;
+    }
+}
+
+// Sends a key to the map's scripts, or those of the screen over it, and
+// passes turns if they made it an action.
+fn press_key(game: &mut GameManager, eq: &mut GameEventQueue, key: KeyCode, cur_turn: &mut u32) -> Result<()> {
+    let context: InputContext = game.get_component_data("InputContext", "game").unwrap_or_default();
+    // screens over the map take the keys for themselves
+    let ev_type = if context.name == "map" { "input.key_press".to_string() } else { format!("input.{}", context.name) };
+    eq.trigger_listeners(game, GameEvent {
+        ev_type,
+        data: serde_json::to_string( &InputData {
+            key_code: key
+        })?
+    });
+
+    // only keys which did something are actions, see InputContext
+    let mut context: InputContext = game.get_component_data("InputContext", "game").unwrap_or_default();
+    if !context.turn_taken {
+        return Ok(())
+    }
+    context.turn_taken = false;
+    game.set_component_data(&context, "game");
+
+    // turns pass when the player is out of actions
+    for _ in 0..stats::end_action(game, "player") {
+        if game.get_component_data::<GameOver>("GameOver", "game").is_some() {
+            break;
+        }
+        *cur_turn += 1;
+        let turn_ev = GameEvent {
+            ev_type: "game.turn".to_string(),
+            data: serde_json::to_string( &TurnData {
+                turn: *cur_turn
+            } )?
+        };
+        eq.trigger_listeners(game, turn_ev);
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+// Every ended game leaves a morgue, returning a note on where it went.
+fn write_morgue(game: &mut GameManager, over: &GameOver, morgue_dir: &Path) -> String {
+    let morgue_text = morgue::compose(game, over);
+    match morgue::write(morgue_dir, &morgue_text) {
+        Ok(path) => format!("Morgue: {}", path.display()),
+        Err(e) => format!("No morgue: {}", e)
+    }
+}
+
+// Shows how the player died until they choose to play again or quit.
+fn game_over(terminal: &mut Terminal<CrosstermBackend<Stdout>>, over: &GameOver, note: &str) -> Result<Outcome> {
+    loop {
+        terminal.draw(rterm::assemble_game_over(over, note))?;
+        match rterm::poll()? {
+            KeyCode::Char('r') => return Ok(Outcome::Restart),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Outcome::Quit),
+            _ => ()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use events::{DamageData, DamageKind};
+    use components::{StatusEffects, StatusEffect, EffectKind, TileMap, TileType, Vector2};
+
+    fn options(seed: u64) -> GameOptions {
+        GameOptions {
+            seed,
+            map_style: "rooms".to_string(),
+            style: StyleOptions::default(),
+            vaults: builtin_prefabs(),
+            map_file: None,
+            morgue_dir: std::env::temp_dir()
+        }
+    }
+
+    #[test]
+    fn dying_ends_the_game_and_restarting_starts_afresh() {
+        let mut options = options(5);
+        let (mut game, mut eq) = new_game(&options).unwrap();
+        let hurt = DamageData { target: "player".to_string(), source: "enemy".to_string(), amount: 500, kind: DamageKind::Physical, cause: None };
+        eq.trigger_listeners(&mut game, hurt.event());
+
+        let over: GameOver = game.get_component_data("GameOver", "game").unwrap();
+        assert_eq!((over.cause.as_str(), over.depth), ("killed by the enemy", 1));
+        assert!(game.get_component_data::<Health>("Health", "player").is_none());
+
+        // what play does when r is pressed on the game over screen
+        options.seed = new_seed(options.seed);
+        assert_ne!(options.seed, 5);
+        let (mut game, _) = new_game(&options).unwrap();
+        assert!(game.get_component_data::<GameOver>("GameOver", "game").is_none());
+        let health: Health = game.get_component_data("Health", "player").unwrap();
+        assert_eq!(health.current_health, health.max_health);
+        assert_eq!(game.get_component_data::<Status>("Status", "player").unwrap().turn, 0);
+        assert_eq!(game.get_component_data::<Levels>("Levels", "dungeon").unwrap().seed, options.seed);
+    }
+
+    #[test]
+    fn keys_which_do_nothing_take_no_time() {
+        let (mut game, mut eq) = new_game(&options(5)).unwrap();
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect { kind: EffectKind::Poison, turns: 5, potency: 1 });
+        game.add_component_from_data(&status, "player");
+        let mut turn = 0;
+        let poison_left = |game: &mut GameManager| game.get_component_data::<StatusEffects>("StatusEffects", "player")
+            .and_then(|s| s.get(EffectKind::Poison).map(|e| e.turns));
+
+        // an unknown key, picking up nothing, firing nothing and closing no door
+        for key in [KeyCode::Char('x'), KeyCode::Char('g'), KeyCode::Char('f'), KeyCode::Char('c')] {
+            press_key(&mut game, &mut eq, key, &mut turn).unwrap();
+        }
+        assert_eq!((turn, poison_left(&mut game)), (0, Some(5)));
+
+        // but a step is a turn
+        let start: WorldPosition = game.get_component_data("WorldPosition", "player").unwrap();
+        let map: TileMap = game.get_component_data("TileMap", &map_object(0)).unwrap();
+        let step = [KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down].into_iter()
+            .find(|key| {
+                let dir = scripts::key_direction(*key).unwrap();
+                start.as_point().offset(dir).and_then(|p| map.get(p.as_tuple())) == Some(TileType::FLOOR)
+            })
+            .unwrap();
+        press_key(&mut game, &mut eq, step, &mut turn).unwrap();
+        assert_eq!((turn, poison_left(&mut game)), (1, Some(4)));
+    }
+}
@@ -0,0 +1,152 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::components::{TileMap, TileType};
+
+// Hand written levels as plain text. A map file has up to three sections:
+//
+//   ; lines starting with a semicolon are comments
+//   [legend]
+//   * = RUBBLE
+//   [map]
+//   #####
+//   #.*.#
+//   #####
+//   [entities]
+//   player 1 1
+//   enemy 3 1
+//
+// The legend adds to (or overrides) the standard characters from
+// TileType::ascii, so it can be left out. Entities are one of
+// ENTITY_NAMES and an x and y. See assets/arena.txt for an example.
+
+// what new_game knows how to place
+pub const ENTITY_NAMES: [&str; 4] = ["player", "enemy", "key", "item"];
+
+#[derive(Clone)]
+pub struct MapFile {
+    pub map: TileMap,
+    // in the order they appear in the file
+    pub entities: Vec<(String, (u16, u16))>
+}
+
+enum Section {
+    Legend,
+    Map,
+    Entities
+}
+
+impl MapFile {
+    pub fn parse(text: &str) -> Result<MapFile> {
+        let mut legend: HashMap<char, TileType> = TileType::ALL.iter().map(|t| (t.ascii(), *t)).collect();
+        let mut rows: Vec<&str> = vec![];
+        let mut entities = vec![];
+        let mut section = None;
+
+        for (i, line) in text.lines().enumerate() {
+            if line.starts_with(';') || line.trim().is_empty() {
+                continue;
+            }
+            match line.trim() {
+                "[legend]" => { section = Some(Section::Legend); continue; },
+                "[map]" => { section = Some(Section::Map); continue; },
+                "[entities]" => { section = Some(Section::Entities); continue; },
+                _ => ()
+            }
+
+            match section {
+                None => return Err(anyhow!("line {}: expected [legend], [map] or [entities]", i + 1)),
+                Some(Section::Legend) => {
+                    let (ch, tile) = line.split_once('=')
+                        .ok_or(anyhow!("line {}: legend entries look like '~ = FLOOR'", i + 1))?;
+                    let mut chars = ch.trim().chars();
+                    let ch = match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => return Err(anyhow!("line {}: a legend entry needs exactly one character", i + 1))
+                    };
+                    let tile = tile.trim().parse::<TileType>().with_context(|| format!("line {}", i + 1))?;
+                    legend.insert(ch, tile);
+                },
+                Some(Section::Map) => rows.push(line),
+                Some(Section::Entities) => {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    let entity = match parts.as_slice() {
+                        [name, _, _] if !ENTITY_NAMES.contains(name) => {
+                            return Err(anyhow!("line {}: unknown entity '{}', expected one of {}", i + 1, name, ENTITY_NAMES.join(", ")))
+                        },
+                        [name, x, y] => (name.to_string(), (
+                            x.parse::<u16>().with_context(|| format!("line {}: bad x", i + 1))?,
+                            y.parse::<u16>().with_context(|| format!("line {}: bad y", i + 1))?
+                        )),
+                        _ => return Err(anyhow!("line {}: entities look like 'name x y'", i + 1))
+                    };
+                    entities.push(entity);
+                }
+            }
+        }
+
+        if rows.is_empty() {
+            return Err(anyhow!("the file has no [map] section"));
+        }
+        let map = TileMap::from_ascii_with(&rows.join("\n"), &legend)?;
+        let size = map.get_size();
+        for (name, pos) in &entities {
+            if pos.0 >= size.0 || pos.1 >= size.1 {
+                return Err(anyhow!("{} at {},{} is off the {}x{} map", name, pos.0, pos.1, size.0, size.1));
+            }
+            let tile = map.tile_at(*pos);
+            if !tile.is_walkable() {
+                return Err(anyhow!("{} at {},{} is stuck in {:?}", name, pos.0, pos.1, tile));
+            }
+        }
+        Ok(MapFile { map, entities })
+    }
+
+    pub fn load(path: &Path) -> Result<MapFile> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        MapFile::parse(&text).with_context(|| format!("in {}", path.display()))
+    }
+
+    // where the first entity with this name stands
+    pub fn entity(&self, name: &str) -> Option<(u16, u16)> {
+        self.entities.iter().find(|(n, _)| n == name).map(|(_, pos)| *pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_legend_and_entities() {
+        let text = "; a test\n[legend]\n~ = floor\n[map]\n#####\n#.~.#\n#####\n[entities]\nplayer 1 1\nenemy 3 1\n";
+        let file = MapFile::parse(text).unwrap();
+        assert_eq!(file.map.tile_at((2, 1)), TileType::FLOOR);
+        assert_eq!(file.entity("player"), Some((1, 1)));
+        assert_eq!(file.entity("enemy"), Some((3, 1)));
+        assert_eq!(file.entity("chest"), None);
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        assert!(MapFile::parse("#####\n").is_err());
+        assert!(MapFile::parse("[map]\n#?#\n").is_err());
+        assert!(MapFile::parse("[legend]\n~ = MAGMA\n[map]\n#~#\n").is_err());
+        assert!(MapFile::parse("[map]\n#.#\n[entities]\nplayer 5 0\n").is_err());
+        assert!(MapFile::parse("[map]\n#.#\n[entities]\nplayer 0 0\n").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_entities() {
+        let err = MapFile::parse("[map]\n#..#\n[entities]\nplayer 1 0\nenemey 2 0\n").err().unwrap();
+        assert_eq!(err.to_string(), "line 5: unknown entity 'enemey', expected one of player, enemy, key, item");
+    }
+
+    #[test]
+    fn example_map_parses() {
+        let file = MapFile::parse(include_str!("../assets/arena.txt")).unwrap();
+        assert!(file.entity("player").is_some());
+    }
+}
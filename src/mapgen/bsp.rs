@@ -0,0 +1,112 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use ratatui::layout::Rect;
+
+use crate::components::{TileMap, TileType};
+use crate::mapgen::{Dungeon, MapGenerator, Room, solid_map, carve_l, is_connected};
+
+/// Binary space partitioning: the map is split in two again and again until
+/// the pieces are small, then each piece gets a room and sibling pieces are
+/// joined by corridors. Rooms come out evenly spread with no overlaps.
+pub struct BspConfig {
+    pub size: (u16, u16),
+    // pieces smaller than twice this aren't split again
+    pub min_leaf: u16
+}
+
+impl Default for BspConfig {
+    fn default() -> Self {
+        Self {
+            size: (80, 40),
+            min_leaf: 8
+        }
+    }
+}
+
+impl MapGenerator for BspConfig {
+    fn generate(&self, seed: u64) -> Dungeon {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut map = solid_map(self.size);
+        let mut rooms = vec![];
+
+        if self.size.0 < 3 || self.size.1 < 3 {
+            return Dungeon::from_rooms(map, rooms);
+        }
+
+        let area = Rect::new(1, 1, self.size.0 - 2, self.size.1 - 2);
+        // leaves need space for a room and a wall on each side
+        let min_leaf = self.min_leaf.max(5);
+        partition(area, min_leaf, &mut rng, &mut map, &mut rooms);
+
+        debug_assert!(is_connected(&map));
+        Dungeon::from_rooms(map, rooms)
+    }
+}
+
+// Splits area, or fills it with a room if it's too small. Returns a point in
+// one of the rooms made so the caller can join it to its sibling.
+fn partition(area: Rect, min_leaf: u16, rng: &mut StdRng, map: &mut TileMap, rooms: &mut Vec<Room>) -> (u16, u16) {
+    let can_split_x = area.width >= min_leaf * 2;
+    let can_split_y = area.height >= min_leaf * 2;
+
+    let split_x = match (can_split_x, can_split_y) {
+        (false, false) => return place_room(area, rng, map, rooms),
+        (true, false) => true,
+        (false, true) => false,
+        // split across the long side so leaves don't get too thin
+        (true, true) if area.width > area.height => true,
+        (true, true) if area.height > area.width => false,
+        (true, true) => rng.gen_bool(0.5)
+    };
+
+    let (first, second) = if split_x {
+        let at = rng.gen_range(min_leaf..=area.width - min_leaf);
+        (
+            Rect { width: at, ..area },
+            Rect { x: area.x + at, width: area.width - at, ..area }
+        )
+    } else {
+        let at = rng.gen_range(min_leaf..=area.height - min_leaf);
+        (
+            Rect { height: at, ..area },
+            Rect { y: area.y + at, height: area.height - at, ..area }
+        )
+    };
+
+    let a = partition(first, min_leaf, rng, map, rooms);
+    let b = partition(second, min_leaf, rng, map, rooms);
+    carve_l(map, a, b, rng.gen_bool(0.5));
+    if rng.gen_bool(0.5) { a } else { b }
+}
+
+fn place_room(leaf: Rect, rng: &mut StdRng, map: &mut TileMap, rooms: &mut Vec<Room>) -> (u16, u16) {
+    // keep a wall between this room and the next leaf over
+    let inset = |len: u16| if len >= 3 { (1, len - 2) } else { (0, len) };
+    let (margin_x, space_w) = inset(leaf.width);
+    let (margin_y, space_h) = inset(leaf.height);
+
+    let w = rng.gen_range(space_w.min(3)..=space_w);
+    let h = rng.gen_range(space_h.min(3)..=space_h);
+    let x = leaf.x + margin_x + rng.gen_range(0..=space_w - w);
+    let y = leaf.y + margin_y + rng.gen_range(0..=space_h - h);
+
+    let room = Room { rect: Rect::new(x, y, w, h) };
+    map.draw_rect(&room.rect, TileType::FLOOR, true);
+    rooms.push(room);
+    room.center()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bsp_rooms_are_spread_out() {
+        let dungeon = BspConfig { size: (60, 30), min_leaf: 8 }.generate(3);
+        assert!(dungeon.rooms.len() >= 4);
+        for (i, room) in dungeon.rooms.iter().enumerate() {
+            for other in &dungeon.rooms[i + 1..] {
+                assert!(!room.touches(other));
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Serialize, Deserialize};
+
+use crate::components::{TileMap, TileType};
+use crate::mapgen::{Dungeon, MapGenerator, solid_map, regions, carve_l, closest_pair, is_connected};
+
+/// What to do with areas of floor cut off from the main cavern.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pockets {
+    // fill them in with wall
+    Remove,
+    // tunnel from each one to the main cavern
+    #[default]
+    Connect
+}
+
+impl FromStr for Pockets {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "remove" => Ok(Pockets::Remove),
+            "connect" => Ok(Pockets::Connect),
+            other => Err(anyhow!("unknown pockets '{}', expected remove or connect", other))
+        }
+    }
+}
+
+/// Cavern generation by cellular automata: the map starts as random noise
+/// and is smoothed until it settles into caves.
+pub struct CavesConfig {
+    pub size: (u16, u16),
+    // chance each tile starts as wall, 0 to 1
+    pub fill_ratio: f64,
+    // smoothing passes
+    pub iterations: u16,
+    pub pockets: Pockets
+}
+
+impl Default for CavesConfig {
+    fn default() -> Self {
+        Self {
+            size: (80, 40),
+            fill_ratio: 0.45,
+            iterations: 4,
+            pockets: Pockets::default()
+        }
+    }
+}
+
+pub fn generate_caves(config: &CavesConfig, seed: u64) -> TileMap {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut map = solid_map(config.size);
+    let (w, h) = config.size;
+    if w < 3 || h < 3 {
+        return map;
+    }
+
+    // the border is always wall so caves never run off the map
+    for x in 1..w - 1 {
+        for y in 1..h - 1 {
+            if !rng.gen_bool(config.fill_ratio.clamp(0.0, 1.0)) {
+                map.set_tile((x, y), TileType::FLOOR);
+            }
+        }
+    }
+
+    for _ in 0..config.iterations {
+        map = smooth(&map);
+    }
+
+    let mut found = regions(&map);
+    if found.len() > 1 {
+        let pockets = found.split_off(1);
+        let main = &found[0];
+        for pocket in pockets {
+            match config.pockets {
+                Pockets::Remove => {
+                    for tile in pocket {
+                        map.set_tile(tile, TileType::WALL);
+                    }
+                },
+                Pockets::Connect => {
+                    let (from, to) = closest_pair(&pocket, main);
+                    carve_l(&mut map, from, to, rng.gen_bool(0.5));
+                }
+            }
+        }
+    }
+
+    debug_assert!(is_connected(&map));
+    map
+}
+
+impl MapGenerator for CavesConfig {
+    fn generate(&self, seed: u64) -> Dungeon {
+        let map = generate_caves(self, seed);
+        Dungeon::from_open_map(map, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+// One pass of the automaton: a tile becomes wall when most of its
+// neighbours are, and floor when most aren't. Off-map counts as wall.
+fn smooth(map: &TileMap) -> TileMap {
+    let (w, h) = map.get_size();
+    let mut next = map.clone();
+    for x in 1..w - 1 {
+        for y in 1..h - 1 {
+            let mut walls = 0;
+            for nx in x - 1..=x + 1 {
+                for ny in y - 1..=y + 1 {
+                    if (nx, ny) != (x, y) && map.tile_at((nx, ny)) == TileType::WALL {
+                        walls += 1;
+                    }
+                }
+            }
+            if walls > 4 {
+                next.set_tile((x, y), TileType::WALL);
+            } else if walls < 4 {
+                next.set_tile((x, y), TileType::FLOOR);
+            }
+        }
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapgen::{walkable_tiles, tests::dump};
+
+    #[test]
+    fn caves_are_seeded_and_connected() {
+        for pockets in [Pockets::Remove, Pockets::Connect] {
+            let config = CavesConfig { pockets, ..CavesConfig::default() };
+            for seed in 0..10 {
+                let a = generate_caves(&config, seed);
+                assert_eq!(dump(&a), dump(&generate_caves(&config, seed)));
+                assert!(is_connected(&a), "seed {} is disconnected", seed);
+                assert_eq!(regions(&a).len(), 1);
+            }
+        }
+        assert_ne!(dump(&generate_caves(&CavesConfig::default(), 1)), dump(&generate_caves(&CavesConfig::default(), 2)));
+    }
+
+    #[test]
+    fn cave_border_is_always_wall() {
+        let config = CavesConfig { size: (20, 10), fill_ratio: 0.0, iterations: 0, ..CavesConfig::default() };
+        let cave = dump(&generate_caves(&config, 7));
+        assert_eq!(cave[0], "#".repeat(20));
+        assert_eq!(cave[9], "#".repeat(20));
+        assert_eq!(cave[4], format!("#{}#", ".".repeat(18)));
+    }
+
+    #[test]
+    fn removing_pockets_keeps_the_largest_cavern() {
+        let connect = CavesConfig { fill_ratio: 0.55, iterations: 2, ..CavesConfig::default() };
+        let remove = CavesConfig { pockets: Pockets::Remove, ..CavesConfig { fill_ratio: 0.55, iterations: 2, ..CavesConfig::default() } };
+        for seed in 0..5 {
+            let connected = walkable_tiles(&generate_caves(&connect, seed));
+            let removed = walkable_tiles(&generate_caves(&remove, seed));
+            assert!(removed.len() < connected.len());
+            assert!(removed.iter().all(|f| connected.contains(f)));
+        }
+    }
+}
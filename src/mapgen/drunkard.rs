@@ -0,0 +1,84 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::components::TileType;
+use crate::mapgen::{Dungeon, MapGenerator, solid_map, walkable_tiles};
+
+/// Drunkard's walk: walkers stagger around at random digging out floor
+/// until enough of the map is open. Each walker after the first sets off
+/// from floor that's already dug, so the result is always connected.
+pub struct DrunkardConfig {
+    pub size: (u16, u16),
+    // how much of the map to dig out, 0 to 1
+    pub floor_ratio: f64,
+    // steps each walker takes before the next one starts
+    pub walk_length: u32
+}
+
+impl Default for DrunkardConfig {
+    fn default() -> Self {
+        Self {
+            size: (80, 40),
+            floor_ratio: 0.4,
+            walk_length: 400
+        }
+    }
+}
+
+// gives up on ratios which can't sensibly be reached
+const MAX_WALKERS: u32 = 10_000;
+
+impl MapGenerator for DrunkardConfig {
+    fn generate(&self, seed: u64) -> Dungeon {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut map = solid_map(self.size);
+        let (w, h) = self.size;
+        if w < 3 || h < 3 {
+            return Dungeon::from_open_map(map, &mut rng);
+        }
+
+        let interior = f64::from(w - 2) * f64::from(h - 2);
+        let target = (interior * self.floor_ratio.clamp(0.0, 1.0)).ceil() as usize;
+        let mut dug = 0;
+        let mut floors = vec![(w / 2, h / 2)];
+
+        for _ in 0..MAX_WALKERS {
+            let mut pos = floors[rng.gen_range(0..floors.len())];
+            for _ in 0..self.walk_length.max(1) {
+                if !map.tile_at(pos).is_walkable() {
+                    map.set_tile(pos, TileType::FLOOR);
+                    floors.push(pos);
+                    dug += 1;
+                    if dug >= target {
+                        break;
+                    }
+                }
+                // stay off the border
+                pos = match rng.gen_range(0..4) {
+                    0 if pos.0 > 1 => (pos.0 - 1, pos.1),
+                    1 if pos.0 < w - 2 => (pos.0 + 1, pos.1),
+                    2 if pos.1 > 1 => (pos.0, pos.1 - 1),
+                    3 if pos.1 < h - 2 => (pos.0, pos.1 + 1),
+                    _ => pos
+                };
+            }
+            if dug >= target {
+                break;
+            }
+        }
+
+        debug_assert_eq!(walkable_tiles(&map).len(), dug);
+        Dungeon::from_open_map(map, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drunkard_digs_the_requested_amount() {
+        let config = DrunkardConfig { size: (40, 20), floor_ratio: 0.3, walk_length: 100 };
+        let dungeon = config.generate(9);
+        assert_eq!(walkable_tiles(&dungeon.map).len(), (38.0f64 * 18.0 * 0.3).ceil() as usize);
+    }
+}
@@ -0,0 +1,399 @@
+// Procedural TileMap generation. Every generator takes a seed and gives the
+// same map back for the same seed and config.
+//
+// This is a toolkit for building levels, so not every option is used by
+// the game itself.
+
+use rand::Rng;
+use ratatui::layout::Rect;
+use serde::{Serialize, Deserialize};
+
+use crate::components::{TileMap, TileType, Point, Direction};
+
+pub use self::rooms::{RoomsConfig, CorridorStyle};
+pub use self::caves::{CavesConfig, Pockets};
+pub use self::bsp::BspConfig;
+pub use self::drunkard::DrunkardConfig;
+pub use self::pipeline::{Pipeline, PostProcess, CullUnreachable, PlaceDoors, AddLoops};
+pub use self::prefab::{Prefab, PlaceVaults, load_prefabs, builtin_prefabs, prefabs_to_text};
+
+mod rooms;
+mod caves;
+mod bsp;
+mod drunkard;
+mod pipeline;
+mod prefab;
+
+/// Anything which can build a level from a seed. Generators and pipelines
+/// of post-processing steps are interchangeable.
+pub trait MapGenerator {
+    fn generate(&self, seed: u64) -> Dungeon;
+}
+
+pub const GENERATOR_NAMES: [&str; 4] = ["rooms", "caves", "bsp", "drunkard"];
+
+/// Choices within the level styles, taken from the command line. Every
+/// level of a dungeon is made with the same ones.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct StyleOptions {
+    // what caves do with areas cut off from the main cavern
+    pub pockets: Pockets,
+    // how rooms are joined up
+    pub corridors: CorridorStyle
+}
+
+/// The level style the game uses for each name in GENERATOR_NAMES. Levels
+/// with rooms get some of the given vaults too.
+pub fn generator_named(name: &str, vaults: &[Prefab], options: &StyleOptions) -> Option<Box<dyn MapGenerator>> {
+    let generator: Box<dyn MapGenerator> = match name {
+        "rooms" => Box::new(Pipeline::new(RoomsConfig { corridors: options.corridors, ..RoomsConfig::default() })
+            .then(PlaceVaults { prefabs: vaults.to_vec(), count: 2 })
+            .then(AddLoops { count: 4, min_shortcut: 30 })
+            .then(PlaceDoors)),
+        "caves" => Box::new(CavesConfig { pockets: options.pockets, ..CavesConfig::default() }),
+        "bsp" => Box::new(Pipeline::new(BspConfig::default())
+            .then(PlaceVaults { prefabs: vaults.to_vec(), count: 1 })
+            .then(AddLoops { count: 6, min_shortcut: 20 })
+            .then(PlaceDoors)),
+        "drunkard" => Box::new(Pipeline::new(DrunkardConfig::default()).then(CullUnreachable)),
+        _ => return None
+    };
+    Some(generator)
+}
+
+/// The seed for each depth of a dungeon, counting from 1. The first level
+/// uses the dungeon's seed as it is, so seeds still replay the same start.
+pub fn level_seed(seed: u64, depth: u16) -> u64 {
+    seed ^ u64::from(depth.saturating_sub(1)).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Level `depth` of a dungeon in the named style, with its stairs placed.
+pub fn generate_level(name: &str, seed: u64, depth: u16, vaults: &[Prefab], options: &StyleOptions) -> Option<Dungeon> {
+    let seed = level_seed(seed, depth);
+    let mut dungeon = generator_named(name, vaults, options)?.generate(seed);
+    dungeon.add_stairs(depth);
+    Some(dungeon)
+}
+
+// how many spawn points open maps without rooms offer
+const OPEN_MAP_SPAWNS: usize = 10;
+
+/// A generated map, plus the rooms carved into it so callers can decide
+/// where to put the player, monsters and stairs. Maps without rooms (caves
+/// and the like) leave `rooms` empty.
+pub struct Dungeon {
+    pub map: TileMap,
+    pub rooms: Vec<Room>,
+    // where the player should start
+    pub start: (u16, u16),
+    // free floor for monsters and stairs, furthest from start first
+    pub spawns: Vec<(u16, u16)>,
+    // places set aside for items, e.g. by vaults
+    pub item_spawns: Vec<(u16, u16)>,
+    // places set aside for monsters, likewise
+    pub monster_spawns: Vec<(u16, u16)>
+}
+
+impl Dungeon {
+    /// Start in the first room, with a spawn point in the middle of each
+    /// of the others.
+    pub fn from_rooms(map: TileMap, rooms: Vec<Room>) -> Self {
+        let start = rooms.first().map_or((0, 0), |r| r.center());
+        let spawns = rooms.iter().skip(1).map(|r| r.center()).collect();
+        let mut dungeon = Self { map, rooms, start, spawns, item_spawns: vec![], monster_spawns: vec![] };
+        dungeon.sort_spawns();
+        dungeon
+    }
+
+    /// Start and spawn on randomly chosen floor tiles.
+    pub fn from_open_map(map: TileMap, rng: &mut impl Rng) -> Self {
+        let mut floors = walkable_tiles(&map);
+        if floors.is_empty() {
+            return Self { map, rooms: vec![], start: (0, 0), spawns: vec![], item_spawns: vec![], monster_spawns: vec![] };
+        }
+        let start = floors.swap_remove(rng.gen_range(0..floors.len()));
+        let mut spawns = vec![];
+        while spawns.len() < OPEN_MAP_SPAWNS && !floors.is_empty() {
+            spawns.push(floors.swap_remove(rng.gen_range(0..floors.len())));
+        }
+        let mut dungeon = Self { map, rooms: vec![], start, spawns, item_spawns: vec![], monster_spawns: vec![] };
+        dungeon.sort_spawns();
+        dungeon
+    }
+
+    /// Stairs down on the furthest spawn point, which is used up unless it's
+    /// the only one, and stairs up on the start below the first level.
+    pub fn add_stairs(&mut self, depth: u16) {
+        if let Some(down) = self.spawns.first().copied() {
+            self.map.set_tile(down, TileType::STAIRS_DOWN);
+            if self.spawns.len() > 1 {
+                self.spawns.remove(0);
+            }
+        }
+        if depth > 1 {
+            self.map.set_tile(self.start, TileType::STAIRS_UP);
+        }
+    }
+
+    pub fn sort_spawns(&mut self) {
+        let start = self.start;
+        self.spawns.sort_by_key(|s| std::cmp::Reverse(s.0.abs_diff(start.0) + s.1.abs_diff(start.1)));
+    }
+}
+
+/// The floor area of a room; its walls are the tiles just outside `rect`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Room {
+    pub rect: Rect
+}
+
+impl Room {
+    pub fn center(&self) -> (u16, u16) {
+        (self.rect.x + self.rect.width / 2, self.rect.y + self.rect.height / 2)
+    }
+
+    /// True if the rooms overlap or would share a wall.
+    pub fn touches(&self, other: &Room) -> bool {
+        let grown = Rect {
+            x: self.rect.x.saturating_sub(1),
+            y: self.rect.y.saturating_sub(1),
+            width: self.rect.width + 2,
+            height: self.rect.height + 2
+        };
+        grown.intersects(other.rect)
+    }
+}
+
+/// Every tile reachable from `start` without passing through walls, indexed
+/// [x][y] like TileMap.
+pub fn flood_fill(map: &TileMap, start: (u16, u16)) -> Vec<Vec<bool>> {
+    let size = map.get_size();
+    let mut seen = vec![vec![false; usize::from(size.1)]; usize::from(size.0)];
+    if start.0 >= size.0 || start.1 >= size.1 || !map.tile_at(start).is_passable() {
+        return seen;
+    }
+
+    let mut stack = vec![start];
+    seen[usize::from(start.0)][usize::from(start.1)] = true;
+    while let Some(pos) = stack.pop() {
+        for (nx, ny) in neighbours(pos, size) {
+            if seen[usize::from(nx)][usize::from(ny)] || !map.tile_at((nx, ny)).is_passable() {
+                continue;
+            }
+            seen[usize::from(nx)][usize::from(ny)] = true;
+            stack.push((nx, ny));
+        }
+    }
+    seen
+}
+
+/// Every tile which can be walked on, not just plain floor.
+pub fn walkable_tiles(map: &TileMap) -> Vec<(u16, u16)> {
+    let size = map.get_size();
+    let mut floors = vec![];
+    for x in 0..size.0 {
+        for y in 0..size.1 {
+            if map.tile_at((x, y)).is_walkable() {
+                floors.push((x, y));
+            }
+        }
+    }
+    floors
+}
+
+/// Cheapest path from `start` to every reachable tile, indexed [x][y],
+/// counting each step as the cost of the tile stepped onto.
+pub fn distances(map: &TileMap, start: (u16, u16)) -> Vec<Vec<Option<u32>>> {
+    let size = map.get_size();
+    let mut dist = vec![vec![None; usize::from(size.1)]; usize::from(size.0)];
+    if start.0 >= size.0 || start.1 >= size.1 || !map.tile_at(start).is_passable() {
+        return dist;
+    }
+
+    // Dijkstra; on maps of plain floor this is a breadth first search
+    let mut queue = std::collections::BinaryHeap::from([std::cmp::Reverse((0, start))]);
+    dist[usize::from(start.0)][usize::from(start.1)] = Some(0);
+    while let Some(std::cmp::Reverse((here, (x, y)))) = queue.pop() {
+        if dist[usize::from(x)][usize::from(y)].is_some_and(|d| d < here) {
+            continue;
+        }
+        for (nx, ny) in neighbours((x, y), size) {
+            let tile = map.tile_at((nx, ny));
+            if !tile.is_passable() {
+                continue;
+            }
+            let there = here + tile.cost();
+            if dist[usize::from(nx)][usize::from(ny)].is_none_or(|d| there < d) {
+                dist[usize::from(nx)][usize::from(ny)] = Some(there);
+                queue.push(std::cmp::Reverse((there, (nx, ny))));
+            }
+        }
+    }
+    dist
+}
+
+// the orthogonal neighbours of pos which are on the map
+pub fn neighbours(pos: (u16, u16), size: (u16, u16)) -> Vec<(u16, u16)> {
+    [Direction::West, Direction::East, Direction::North, Direction::South]
+        .into_iter()
+        .filter_map(|dir| Point::from(pos).offset_within(dir, size))
+        .map(|p| p.as_tuple())
+        .collect()
+}
+
+/// A map of the given size which is wall everywhere.
+pub fn solid_map(size: (u16, u16)) -> TileMap {
+    let mut map = TileMap::new(size);
+    map.draw_rect(&map.to_rect(), TileType::WALL, true);
+    map
+}
+
+/// Every separate area of floor, largest first.
+pub fn regions(map: &TileMap) -> Vec<Vec<(u16, u16)>> {
+    let size = map.get_size();
+    let mut assigned = vec![vec![false; usize::from(size.1)]; usize::from(size.0)];
+    let mut found = vec![];
+
+    for (x, y) in walkable_tiles(map) {
+        if assigned[usize::from(x)][usize::from(y)] {
+            continue;
+        }
+        let reached = flood_fill(map, (x, y));
+        let mut region = vec![];
+        for (rx, column) in reached.iter().enumerate() {
+            for (ry, hit) in column.iter().enumerate() {
+                if *hit {
+                    assigned[rx][ry] = true;
+                    region.push((rx as u16, ry as u16));
+                }
+            }
+        }
+        found.push(region);
+    }
+
+    // stable, so equal sized regions keep their scan order
+    found.sort_by_key(|r| std::cmp::Reverse(r.len()));
+    found
+}
+
+pub fn carve_l(map: &mut TileMap, from: (u16, u16), to: (u16, u16), horizontal_first: bool) {
+    let corner = if horizontal_first { (to.0, from.1) } else { (from.0, to.1) };
+    carve_line(map, from, corner);
+    carve_line(map, corner, to);
+}
+
+// carves a straight horizontal or vertical line, inclusive of both ends
+fn carve_line(map: &mut TileMap, from: (u16, u16), to: (u16, u16)) {
+    let rect = Rect {
+        x: from.0.min(to.0),
+        y: from.1.min(to.1),
+        width: from.0.abs_diff(to.0) + 1,
+        height: from.1.abs_diff(to.1) + 1
+    };
+    map.draw_rect(&rect, TileType::FLOOR, true);
+}
+
+pub fn carve_winding(map: &mut TileMap, from: (u16, u16), to: (u16, u16), rng: &mut impl Rng) {
+    let mut pos = from;
+    map.set_tile(pos, TileType::FLOOR);
+    // every step gets closer, so this always arrives
+    while pos != to {
+        let move_x = if pos.0 == to.0 {
+            false
+        } else if pos.1 == to.1 {
+            true
+        } else {
+            rng.gen_bool(0.5)
+        };
+
+        if move_x {
+            pos.0 = if to.0 > pos.0 { pos.0 + 1 } else { pos.0 - 1 };
+        } else {
+            pos.1 = if to.1 > pos.1 { pos.1 + 1 } else { pos.1 - 1 };
+        }
+        map.set_tile(pos, TileType::FLOOR);
+    }
+}
+
+/// The nearest tiles of two areas, by Manhattan distance.
+pub fn closest_pair(a: &[(u16, u16)], b: &[(u16, u16)]) -> ((u16, u16), (u16, u16)) {
+    let mut best = (a[0], b[0]);
+    let mut best_dist = u32::MAX;
+    for p in a {
+        for q in b {
+            let dist = u32::from(p.0.abs_diff(q.0)) + u32::from(p.1.abs_diff(q.1));
+            if dist < best_dist {
+                best = (*p, *q);
+                best_dist = dist;
+            }
+        }
+    }
+    best
+}
+
+/// Whether every walkable tile can be reached from every other.
+pub fn is_connected(map: &TileMap) -> bool {
+    let floors = walkable_tiles(map);
+    let first = match floors.first() {
+        None => return true,
+        Some(f) => *f
+    };
+    let reached = flood_fill(map, first);
+    floors.iter().all(|f| reached[usize::from(f.0)][usize::from(f.1)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // walls, floors and doors as text, for comparing maps
+    pub(super) fn dump(map: &TileMap) -> Vec<String> {
+        let size = map.get_size();
+        (0..size.1)
+            .map(|y| (0..size.0).map(|x| match map.tile_at((x, y)) { TileType::FLOOR => '.', TileType::DOOR => '+', _ => '#' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn every_named_generator_is_seeded_connected_and_has_spawns() {
+        for name in GENERATOR_NAMES {
+            for seed in 0..5 {
+                let a = generator_named(name, &builtin_prefabs(), &StyleOptions::default()).unwrap().generate(seed);
+                let b = generator_named(name, &builtin_prefabs(), &StyleOptions::default()).unwrap().generate(seed);
+                assert_eq!(dump(&a.map), dump(&b.map), "{} {}", name, seed);
+                assert_eq!((a.start, &a.spawns), (b.start, &b.spawns));
+                assert!(is_connected(&a.map), "{} {} is disconnected", name, seed);
+                assert_eq!(a.map.tile_at(a.start), TileType::FLOOR);
+                assert!(!a.spawns.is_empty());
+                assert!(a.spawns.iter().all(|s| a.map.tile_at(*s).is_walkable() && *s != a.start));
+            }
+        }
+        assert!(generator_named("maze", &[], &StyleOptions::default()).is_none());
+    }
+
+    #[test]
+    fn distances_count_tile_cost() {
+        let map = TileMap::from_ascii("#####\n#.~.#\n#...#\n#####\n").unwrap();
+        let dist = distances(&map, (1, 1));
+        // stepping into the water costs two
+        assert_eq!(dist[2][1], Some(2));
+        assert_eq!(dist[3][1], Some(3));
+        assert_eq!(dist[2][2], Some(2));
+    }
+
+    #[test]
+    fn levels_get_stairs() {
+        assert_eq!(level_seed(42, 1), 42);
+        assert_ne!(level_seed(42, 2), 42);
+
+        let first = generate_level("rooms", 42, 1, &[], &StyleOptions::default()).unwrap();
+        let down = first.map.find(TileType::STAIRS_DOWN).unwrap();
+        assert!(first.map.find(TileType::STAIRS_UP).is_none());
+        assert!(!first.spawns.contains(&down));
+        assert!(flood_fill(&first.map, first.start)[usize::from(down.0)][usize::from(down.1)]);
+
+        let second = generate_level("rooms", 42, 2, &[], &StyleOptions::default()).unwrap();
+        assert_eq!(second.map.find(TileType::STAIRS_UP), Some(second.start));
+        assert!(second.map.find(TileType::STAIRS_DOWN).is_some());
+    }
+}
@@ -0,0 +1,214 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::components::TileType;
+use crate::mapgen::{Dungeon, MapGenerator, flood_fill, distances};
+
+/// A step run over a finished map, e.g. to tidy it up or decorate it.
+pub trait PostProcess {
+    fn apply(&self, dungeon: &mut Dungeon, rng: &mut StdRng);
+}
+
+/// A generator followed by any number of post-processing steps, applied in
+/// order. Pipelines are generators themselves so they can be nested.
+pub struct Pipeline {
+    generator: Box<dyn MapGenerator>,
+    steps: Vec<Box<dyn PostProcess>>
+}
+
+impl Pipeline {
+    pub fn new(generator: impl MapGenerator + 'static) -> Self {
+        Self {
+            generator: Box::new(generator),
+            steps: vec![]
+        }
+    }
+
+    pub fn then(mut self, step: impl PostProcess + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+}
+
+impl MapGenerator for Pipeline {
+    fn generate(&self, seed: u64) -> Dungeon {
+        let mut dungeon = self.generator.generate(seed);
+        // steps get their own stream so adding one doesn't change the map
+        // underneath it
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(0x9e37_79b9_7f4a_7c15));
+        for step in &self.steps {
+            step.apply(&mut dungeon, &mut rng);
+        }
+        dungeon
+    }
+}
+
+/// Walls over anything the player can't walk to from the start, and drops
+/// spawns and rooms which were cut off.
+pub struct CullUnreachable;
+
+impl PostProcess for CullUnreachable {
+    fn apply(&self, dungeon: &mut Dungeon, _rng: &mut StdRng) {
+        let reached = flood_fill(&dungeon.map, dungeon.start);
+        let is_reached = |p: (u16, u16)| reached[usize::from(p.0)][usize::from(p.1)];
+        let size = dungeon.map.get_size();
+
+        for x in 0..size.0 {
+            for y in 0..size.1 {
+                if !is_reached((x, y)) && dungeon.map.tile_at((x, y)).is_passable() {
+                    dungeon.map.set_tile((x, y), TileType::WALL);
+                }
+            }
+        }
+        dungeon.spawns.retain(|s| is_reached(*s));
+        dungeon.rooms.retain(|r| is_reached(r.center()));
+    }
+}
+
+/// Puts a door wherever a corridor meets a room's wall. Maps without rooms
+/// are left alone.
+pub struct PlaceDoors;
+
+impl PostProcess for PlaceDoors {
+    fn apply(&self, dungeon: &mut Dungeon, _rng: &mut StdRng) {
+        let map = &mut dungeon.map;
+        let size = map.get_size();
+        let wall = |map: &crate::components::TileMap, x: u16, y: u16| {
+            x >= size.0 || y >= size.1 || !map.tile_at((x, y)).is_passable()
+        };
+
+        for room in &dungeon.rooms {
+            let r = room.rect;
+            if r.x == 0 || r.y == 0 {
+                continue;
+            }
+            // the wall ring around the room, minus its corners
+            let mut entrances = vec![];
+            for x in r.left()..r.right() {
+                for y in [r.top() - 1, r.bottom()] {
+                    if y < size.1 && map.tile_at((x, y)) == TileType::FLOOR
+                        && wall(map, x - 1, y) && wall(map, x + 1, y) {
+                        entrances.push((x, y));
+                    }
+                }
+            }
+            for y in r.top()..r.bottom() {
+                for x in [r.left() - 1, r.right()] {
+                    if x < size.0 && map.tile_at((x, y)) == TileType::FLOOR
+                        && wall(map, x, y - 1) && wall(map, x, y + 1) {
+                        entrances.push((x, y));
+                    }
+                }
+            }
+            for e in entrances {
+                map.set_tile(e, TileType::DOOR);
+            }
+        }
+    }
+}
+
+/// Knocks through walls which separate two bits of floor that are a long
+/// way apart on foot, so the map isn't all dead ends.
+pub struct AddLoops {
+    pub count: u16,
+    // the walk a new opening has to save for it to be worth making
+    pub min_shortcut: u32
+}
+
+impl PostProcess for AddLoops {
+    fn apply(&self, dungeon: &mut Dungeon, rng: &mut StdRng) {
+        let map = &mut dungeon.map;
+        let (w, h) = map.get_size();
+        if w < 3 || h < 3 {
+            return;
+        }
+
+        // walls with floor either side in a straight line
+        let mut candidates = vec![];
+        for x in 1..w - 1 {
+            for y in 1..h - 1 {
+                if map.tile_at((x, y)) != TileType::WALL {
+                    continue;
+                }
+                let open = |p: (u16, u16)| map.tile_at(p).is_walkable();
+                if open((x - 1, y)) && open((x + 1, y)) && !open((x, y - 1)) && !open((x, y + 1)) {
+                    candidates.push(((x, y), (x - 1, y), (x + 1, y)));
+                } else if open((x, y - 1)) && open((x, y + 1)) && !open((x - 1, y)) && !open((x + 1, y)) {
+                    candidates.push(((x, y), (x, y - 1), (x, y + 1)));
+                }
+            }
+        }
+
+        let mut made = 0;
+        while made < self.count && !candidates.is_empty() {
+            let (wall, a, b) = candidates.swap_remove(rng.gen_range(0..candidates.len()));
+            let dist = distances(map, a)[usize::from(b.0)][usize::from(b.1)];
+            // unreachable counts as far away; joining it up is a bonus
+            if dist.is_none_or(|d| d >= self.min_shortcut) {
+                map.set_tile(wall, TileType::FLOOR);
+                made += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use crate::mapgen::{Room, solid_map, carve_l, tests::dump};
+
+    // a room on the left joined to one on the right, plus an island
+    fn two_rooms() -> Dungeon {
+        let mut map = solid_map((20, 9));
+        let left = Room { rect: Rect::new(1, 1, 5, 5) };
+        let right = Room { rect: Rect::new(12, 1, 5, 5) };
+        map.draw_rect(&left.rect, TileType::FLOOR, true);
+        map.draw_rect(&right.rect, TileType::FLOOR, true);
+        carve_l(&mut map, (5, 3), (12, 3), true);
+        map.set_tile((9, 7), TileType::FLOOR);
+        Dungeon::from_rooms(map, vec![left, right])
+    }
+
+    struct Fixed;
+
+    impl MapGenerator for Fixed {
+        fn generate(&self, _seed: u64) -> Dungeon {
+            two_rooms()
+        }
+    }
+
+    #[test]
+    fn pipeline_steps_run_in_order() {
+        let dungeon = Pipeline::new(Fixed).then(CullUnreachable).then(PlaceDoors).generate(0);
+        assert_eq!(dump(&dungeon.map), vec![
+            "####################",
+            "#.....######.....###",
+            "#.....######.....###",
+            "#.....+....+.....###",
+            "#.....######.....###",
+            "#.....######.....###",
+            "####################",
+            "####################",
+            "####################",
+        ]);
+        assert_eq!(dungeon.spawns, vec![(14, 3)]);
+    }
+
+    #[test]
+    fn loops_only_open_worthwhile_shortcuts() {
+        let mut map = solid_map((9, 7));
+        // a U shaped corridor: the two arms are 1 wall apart but 8 steps apart on foot
+        carve_l(&mut map, (1, 1), (1, 5), true);
+        carve_l(&mut map, (1, 5), (3, 5), true);
+        carve_l(&mut map, (3, 5), (3, 1), true);
+        let mut dungeon = Dungeon::from_open_map(map, &mut StdRng::seed_from_u64(0));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        AddLoops { count: 10, min_shortcut: 100 }.apply(&mut dungeon, &mut rng);
+        assert_eq!(dungeon.map.tile_at((2, 1)), TileType::WALL);
+
+        AddLoops { count: 1, min_shortcut: 5 }.apply(&mut dungeon, &mut rng);
+        let opened = [(2, 1), (2, 2), (2, 3)].iter().filter(|p| dungeon.map.tile_at(**p) == TileType::FLOOR).count();
+        assert_eq!(opened, 1);
+    }
+}
@@ -0,0 +1,319 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use rand::{Rng, rngs::StdRng};
+
+use crate::components::{TileType, Point, Direction};
+use crate::mapgen::{Dungeon, PostProcess, flood_fill, closest_pair, carve_l};
+
+// Hand made rooms ("vaults") drawn as ASCII art. See assets/vaults.txt for
+// the format.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Marker {
+    Monster,
+    Item
+}
+
+// what a prefab puts on one tile; no tile leaves the map untouched
+type PrefabCell = (Option<TileType>, Option<Marker>);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Prefab {
+    // indexed [y][x], every row the same length
+    cells: Vec<Vec<PrefabCell>>
+}
+
+impl Prefab {
+    /// Every prefab in a file of them, separated by blank lines.
+    pub fn parse(text: &str) -> Result<Vec<Prefab>> {
+        let mut prefabs = vec![];
+        let mut rows: Vec<Vec<PrefabCell>> = vec![];
+
+        for (i, line) in text.lines().enumerate() {
+            if line.starts_with(';') {
+                continue;
+            }
+            if line.trim().is_empty() {
+                if !rows.is_empty() {
+                    prefabs.push(Prefab::from_rows(std::mem::take(&mut rows)));
+                }
+                continue;
+            }
+
+            let mut row = vec![];
+            for ch in line.chars() {
+                row.push(match ch {
+                    'M' => (Some(TileType::FLOOR), Some(Marker::Monster)),
+                    '!' => (Some(TileType::FLOOR), Some(Marker::Item)),
+                    '?' | ' ' => (None, None),
+                    other => match TileType::from_ascii(other) {
+                        Some(tile) => (Some(tile), None),
+                        None => return Err(anyhow!("line {}: '{}' isn't a prefab tile", i + 1, other))
+                    }
+                });
+            }
+            rows.push(row);
+        }
+        if !rows.is_empty() {
+            prefabs.push(Prefab::from_rows(rows));
+        }
+        // it's joined to the map by a single tunnel, so every part has to
+        // be reachable from every other
+        for (i, prefab) in prefabs.iter().enumerate() {
+            if let Some((x, y)) = prefab.unreachable().first() {
+                return Err(anyhow!("prefab {}: {},{} can't be reached from the rest of it", i + 1, x, y));
+            }
+        }
+        Ok(prefabs)
+    }
+
+    // pads short rows so the prefab is rectangular
+    fn from_rows(mut rows: Vec<Vec<PrefabCell>>) -> Self {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        for row in &mut rows {
+            row.resize(width, (None, None));
+        }
+        Self { cells: rows }
+    }
+
+    /// The cells which can be walked through, doors included.
+    pub fn open_cells(&self) -> Vec<(u16, u16)> {
+        let mut open = vec![];
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.0.is_some_and(|t| t.is_passable()) {
+                    open.push((x as u16, y as u16));
+                }
+            }
+        }
+        open
+    }
+
+    /// Open cells which can't be walked to from the first one.
+    pub fn unreachable(&self) -> Vec<(u16, u16)> {
+        let open = self.open_cells();
+        let mut reached = vec![];
+        let mut frontier: Vec<(u16, u16)> = open.first().copied().into_iter().collect();
+        while let Some(pos) = frontier.pop() {
+            if reached.contains(&pos) {
+                continue;
+            }
+            reached.push(pos);
+            for dir in Direction::CARDINAL {
+                if let Some(next) = Point::from(pos).offset(dir).map(<(u16, u16)>::from) {
+                    if open.contains(&next) && !reached.contains(&next) {
+                        frontier.push(next);
+                    }
+                }
+            }
+        }
+        open.into_iter().filter(|pos| !reached.contains(pos)).collect()
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        let height = self.cells.len();
+        let width = self.cells.first().map_or(0, |r| r.len());
+        (width as u16, height as u16)
+    }
+
+    /// Turned a quarter turn clockwise.
+    pub fn rotated(&self) -> Self {
+        let (w, h) = self.size();
+        let cells = (0..usize::from(w))
+            .map(|x| (0..usize::from(h)).rev().map(|y| self.cells[y][x]).collect())
+            .collect();
+        Self { cells }
+    }
+
+    /// Flipped left to right.
+    pub fn mirrored(&self) -> Self {
+        let cells = self.cells.iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+        Self { cells }
+    }
+
+    /// One of the eight rotations and reflections, 0 being unchanged.
+    pub fn oriented(&self, orientation: u8) -> Self {
+        let mut prefab = if orientation & 4 != 0 { self.mirrored() } else { self.clone() };
+        for _ in 0..orientation % 4 {
+            prefab = prefab.rotated();
+        }
+        prefab
+    }
+
+    pub fn to_ascii(&self) -> Vec<String> {
+        self.cells.iter()
+            .map(|row| row.iter().map(|cell| match cell {
+                (_, Some(Marker::Monster)) => 'M',
+                (_, Some(Marker::Item)) => '!',
+                (Some(tile), _) => tile.ascii(),
+                (None, _) => '?'
+            }).collect())
+            .collect()
+    }
+}
+
+pub fn load_prefabs(path: &Path) -> Result<Vec<Prefab>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Prefab::parse(&text).with_context(|| format!("in {}", path.display()))
+}
+
+/// Prefabs in the format Prefab::parse reads.
+pub fn prefabs_to_text(prefabs: &[Prefab]) -> String {
+    prefabs.iter().map(|p| p.to_ascii().join("\n")).collect::<Vec<_>>().join("\n\n")
+}
+
+/// The vaults in assets/vaults.txt, built into the game.
+pub fn builtin_prefabs() -> Vec<Prefab> {
+    Prefab::parse(include_str!("../../assets/vaults.txt")).expect("built in vaults should parse")
+}
+
+/// Stamps up to `count` randomly chosen and oriented prefabs into solid
+/// rock, then tunnels each one to the rest of the map.
+pub struct PlaceVaults {
+    pub prefabs: Vec<Prefab>,
+    pub count: u16
+}
+
+// places tried for each vault before giving up on it
+const PLACEMENT_ATTEMPTS: u16 = 50;
+
+impl PostProcess for PlaceVaults {
+    fn apply(&self, dungeon: &mut Dungeon, rng: &mut StdRng) {
+        if self.prefabs.is_empty() {
+            return;
+        }
+        for _ in 0..self.count {
+            let prefab = self.prefabs[rng.gen_range(0..self.prefabs.len())].oriented(rng.gen_range(0..8));
+            for _ in 0..PLACEMENT_ATTEMPTS {
+                if let Some(pos) = random_spot(dungeon, &prefab, rng) {
+                    stamp(dungeon, &prefab, pos, rng);
+                    break;
+                }
+            }
+        }
+        dungeon.sort_spawns();
+    }
+}
+
+// A top left corner where the prefab and a wall around it fit in rock, if
+// the randomly chosen one works.
+fn random_spot(dungeon: &Dungeon, prefab: &Prefab, rng: &mut StdRng) -> Option<(u16, u16)> {
+    let (mw, mh) = dungeon.map.get_size();
+    let (pw, ph) = prefab.size();
+    if pw == 0 || ph == 0 || mw < pw + 2 || mh < ph + 2 {
+        return None;
+    }
+    let x = rng.gen_range(1..=mw - 1 - pw);
+    let y = rng.gen_range(1..=mh - 1 - ph);
+
+    for tx in x - 1..=x + pw {
+        for ty in y - 1..=y + ph {
+            if dungeon.map.tile_at((tx, ty)) != TileType::WALL {
+                return None;
+            }
+        }
+    }
+    Some((x, y))
+}
+
+fn stamp(dungeon: &mut Dungeon, prefab: &Prefab, at: (u16, u16), rng: &mut StdRng) {
+    let mut walkable = vec![];
+    for (dy, row) in prefab.cells.iter().enumerate() {
+        for (dx, cell) in row.iter().enumerate() {
+            let pos = (at.0 + dx as u16, at.1 + dy as u16);
+            if let Some(tile) = cell.0 {
+                dungeon.map.set_tile(pos, tile);
+                if tile.is_passable() {
+                    walkable.push(pos);
+                }
+            }
+            match cell.1 {
+                Some(Marker::Monster) => dungeon.monster_spawns.push(pos),
+                Some(Marker::Item) => dungeon.item_spawns.push(pos),
+                None => ()
+            }
+        }
+    }
+
+    if walkable.is_empty() {
+        return;
+    }
+    let reached = flood_fill(&dungeon.map, dungeon.start);
+    let (w, h) = dungeon.map.get_size();
+    let mut main = vec![];
+    for x in 0..w {
+        for y in 0..h {
+            if reached[usize::from(x)][usize::from(y)] {
+                main.push((x, y));
+            }
+        }
+    }
+    if main.is_empty() || walkable.iter().any(|p| reached[usize::from(p.0)][usize::from(p.1)]) {
+        return;
+    }
+    let (from, to) = closest_pair(&walkable, &main);
+    carve_l(&mut dungeon.map, from, to, rng.gen_bool(0.5));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use ratatui::layout::Rect;
+    use crate::mapgen::{Room, solid_map, is_connected, walkable_tiles, tests::dump};
+
+    #[test]
+    fn prefabs_parse_rotate_and_mirror() {
+        let prefabs = Prefab::parse("; a comment\n#.M\n+!\n\n\n##\n").unwrap();
+        assert_eq!(prefabs.len(), 2);
+        let vault = &prefabs[0];
+        assert_eq!(vault.size(), (3, 2));
+        assert_eq!(vault.to_ascii(), vec!["#.M", "+!?"]);
+        assert_eq!(vault.rotated().to_ascii(), vec!["+#", "!.", "?M"]);
+        assert_eq!(vault.mirrored().to_ascii(), vec!["M.#", "?!+"]);
+        assert_eq!(vault.oriented(4), vault.mirrored());
+        assert_eq!(vault.oriented(2), vault.rotated().rotated());
+        assert_eq!(vault.rotated().rotated().rotated().rotated(), *vault);
+
+        let err = Prefab::parse("##\n#x\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: 'x' isn't a prefab tile");
+        let err = Prefab::parse("#####\n#.#!#\n#####\n").unwrap_err();
+        assert_eq!(err.to_string(), "prefab 1: 3,1 can't be reached from the rest of it");
+        assert!(!builtin_prefabs().is_empty());
+    }
+
+    #[test]
+    fn builtin_vaults_are_closed_and_all_reachable() {
+        for vault in builtin_prefabs() {
+            assert!(vault.unreachable().is_empty(), "{:?}", vault.to_ascii());
+            // an opening in the border would lead into solid rock
+            let (w, h) = vault.size();
+            let on_border = vault.open_cells().into_iter().any(|(x, y)| x == 0 || y == 0 || x == w - 1 || y == h - 1);
+            assert!(!on_border, "{:?}", vault.to_ascii());
+        }
+    }
+
+    #[test]
+    fn vaults_are_stamped_in_rock_and_joined_up() {
+        let vault = Prefab::parse("#####\n#M.!#\n#####\n").unwrap();
+        let mut map = solid_map((20, 12));
+        let room = Room { rect: Rect::new(1, 1, 5, 5) };
+        map.draw_rect(&room.rect, TileType::FLOOR, true);
+        let mut dungeon = Dungeon::from_rooms(map, vec![room]);
+        PlaceVaults { prefabs: vault, count: 1 }.apply(&mut dungeon, &mut StdRng::seed_from_u64(5));
+
+        assert!(is_connected(&dungeon.map));
+        assert_eq!(dungeon.item_spawns.len(), 1);
+        assert_eq!(dungeon.monster_spawns.len(), 1);
+        let item = dungeon.item_spawns[0];
+        assert_eq!(dungeon.map.tile_at(item), TileType::FLOOR);
+        assert_eq!(dungeon.map.tile_at(dungeon.monster_spawns[0]), TileType::FLOOR);
+        // the room is untouched
+        assert_eq!(dump(&dungeon.map)[1..6].iter().map(|l| &l[0..6]).collect::<Vec<_>>(), vec!["#....."; 5]);
+        assert!(walkable_tiles(&dungeon.map).len() > 25 + 3);
+    }
+}
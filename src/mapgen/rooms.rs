@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use ratatui::layout::Rect;
+use serde::{Serialize, Deserialize};
+
+use crate::components::TileType;
+use crate::mapgen::{Dungeon, MapGenerator, Room, solid_map, is_connected, carve_l, carve_winding};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorridorStyle {
+    // one horizontal and one vertical leg
+    #[default]
+    LShaped,
+    // a random staircase towards the target
+    Winding
+}
+
+impl FromStr for CorridorStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "l-shaped" => Ok(CorridorStyle::LShaped),
+            "winding" => Ok(CorridorStyle::Winding),
+            other => Err(anyhow!("unknown corridors '{}', expected l-shaped or winding", other))
+        }
+    }
+}
+
+/// Classic rooms-and-corridors dungeon. Rooms are placed at random and
+/// rejected if they'd overlap an earlier one, then joined in a chain so the
+/// whole map is connected.
+pub struct RoomsConfig {
+    pub size: (u16, u16),
+    // placement attempts; fewer rooms are made if they don't fit
+    pub max_rooms: u16,
+    pub min_room: u16,
+    pub max_room: u16,
+    pub corridors: CorridorStyle
+}
+
+impl Default for RoomsConfig {
+    fn default() -> Self {
+        Self {
+            size: (80, 40),
+            max_rooms: 30,
+            min_room: 4,
+            max_room: 10,
+            corridors: CorridorStyle::default()
+        }
+    }
+}
+
+pub fn generate_rooms(config: &RoomsConfig, seed: u64) -> Dungeon {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut map = solid_map(config.size);
+    let mut rooms: Vec<Room> = vec![];
+
+    // rooms need a wall on every side
+    if config.size.0 < 3 || config.size.1 < 3 {
+        return Dungeon::from_rooms(map, rooms);
+    }
+    let max_w = config.max_room.min(config.size.0 - 2).max(1);
+    let max_h = config.max_room.min(config.size.1 - 2).max(1);
+    let min_w = config.min_room.clamp(1, max_w);
+    let min_h = config.min_room.clamp(1, max_h);
+
+    for _ in 0..config.max_rooms {
+        let w = rng.gen_range(min_w..=max_w);
+        let h = rng.gen_range(min_h..=max_h);
+        let x = rng.gen_range(1..=config.size.0 - 1 - w);
+        let y = rng.gen_range(1..=config.size.1 - 1 - h);
+        let room = Room { rect: Rect::new(x, y, w, h) };
+
+        if rooms.iter().any(|r| r.touches(&room)) {
+            continue;
+        }
+        rooms.push(room);
+    }
+
+    if rooms.is_empty() {
+        rooms.push(Room { rect: Rect::new(1, 1, max_w, max_h) });
+    }
+
+    for room in &rooms {
+        map.draw_rect(&room.rect, TileType::FLOOR, true);
+    }
+
+    // joining neighbours left to right keeps corridors short
+    rooms.sort_by_key(|r| r.center());
+    for pair in rooms.windows(2) {
+        let from = pair[0].center();
+        let to = pair[1].center();
+        match config.corridors {
+            CorridorStyle::LShaped => carve_l(&mut map, from, to, rng.gen_bool(0.5)),
+            CorridorStyle::Winding => carve_winding(&mut map, from, to, &mut rng)
+        }
+    }
+
+    debug_assert!(is_connected(&map));
+    Dungeon::from_rooms(map, rooms)
+}
+
+impl MapGenerator for RoomsConfig {
+    fn generate(&self, seed: u64) -> Dungeon {
+        generate_rooms(self, seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapgen::tests::dump;
+
+    #[test]
+    fn rooms_are_seeded_and_connected() {
+        for style in [CorridorStyle::LShaped, CorridorStyle::Winding] {
+            let config = RoomsConfig { corridors: style, ..RoomsConfig::default() };
+            for seed in 0..20 {
+                let a = generate_rooms(&config, seed);
+                let b = generate_rooms(&config, seed);
+                assert_eq!(dump(&a.map), dump(&b.map));
+                assert_eq!(a.rooms, b.rooms);
+                assert!(!a.rooms.is_empty());
+                assert!(is_connected(&a.map), "seed {} is disconnected", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn rooms_never_overlap_or_touch_the_edge() {
+        let config = RoomsConfig { size: (50, 20), ..RoomsConfig::default() };
+        for seed in 0..20 {
+            let dungeon = generate_rooms(&config, seed);
+            let bounds = Rect::new(1, 1, 48, 18);
+            for (i, room) in dungeon.rooms.iter().enumerate() {
+                assert_eq!(bounds.intersection(room.rect), room.rect);
+                for other in &dungeon.rooms[i + 1..] {
+                    assert!(!room.touches(other));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiny_maps_still_get_a_room() {
+        let dungeon = generate_rooms(&RoomsConfig { size: (3, 3), ..RoomsConfig::default() }, 1);
+        assert_eq!(dump(&dungeon.map), vec!["###", "#.#", "###"]);
+        assert_eq!(generate_rooms(&RoomsConfig { size: (2, 9), ..RoomsConfig::default() }, 1).rooms, vec![]);
+    }
+
+    #[test]
+    fn rooms_are_joined_the_chosen_way() {
+        let l_shaped = RoomsConfig { corridors: "l-shaped".parse().unwrap(), ..RoomsConfig::default() };
+        let winding = RoomsConfig { corridors: "winding".parse().unwrap(), ..RoomsConfig::default() };
+        assert_ne!(dump(&generate_rooms(&l_shaped, 7).map), dump(&generate_rooms(&winding, 7).map));
+        assert!("diagonal".parse::<CorridorStyle>().is_err());
+    }
+}
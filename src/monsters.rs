@@ -0,0 +1,60 @@
+use crate::components::{CombatStats, Glyph, Health, HealthBar, Hostile, WorldPosition};
+use crate::events::{GameEvent, SpawnData};
+use crate::game::GameManager;
+
+// The things which live in the dungeon. Each level gets one, tougher the
+// deeper it is.
+
+pub struct MonsterTemplate {
+    pub name: &'static str,
+    pub glyph: char,
+    pub health: u16,
+    // dice notation, see CombatStats
+    pub damage: &'static str,
+    pub defense: i32
+}
+
+// by the depth they live at, the last going on down forever
+pub fn monster_templates() -> Vec<MonsterTemplate> {
+    vec![
+        MonsterTemplate { name: "enemy", glyph: 'M', health: 10, damage: "1d4", defense: 0 },
+        MonsterTemplate { name: "goblin", glyph: 'g', health: 12, damage: "1d6", defense: 0 },
+        MonsterTemplate { name: "orc", glyph: 'o', health: 16, damage: "1d8", defense: 1 },
+        MonsterTemplate { name: "troll", glyph: 'T', health: 24, damage: "2d6", defense: 2 }
+    ]
+}
+
+pub fn monster_for_depth(depth: u16) -> MonsterTemplate {
+    let mut templates = monster_templates();
+    let i = usize::from(depth.saturating_sub(1)).min(templates.len() - 1);
+    templates.swap_remove(i)
+}
+
+/// Puts `template` on the map as `obj_id`. It only acts once the queue has
+/// given it the "monster" listeners, e.g. by the event spawn_monster sends.
+pub fn spawn_monster(game: &mut GameManager, template: &MonsterTemplate, obj_id: &str, pos: WorldPosition) {
+    game.add_component_from_data(&Glyph { glyph: template.glyph }, obj_id);
+    game.add_component_from_data(&Health { current_health: template.health, max_health: template.health }, obj_id);
+    game.add_component_from_data(&HealthBar { visible: false }, obj_id);
+    game.add_component_from_data(&CombatStats {
+        damage: template.damage.to_string(),
+        defense: template.defense,
+        ..CombatStats::default()
+    }, obj_id);
+    game.add_component_from_data(&Hostile, obj_id);
+    game.add_component_from_data(&pos, obj_id);
+}
+
+/// The monster for level `map` at depth `depth`, in its lair. Its name is
+/// its id, unless one of its kind is already about.
+pub fn place_monster(game: &mut GameManager, map: u16, depth: u16, lair: (u16, u16)) -> GameEvent {
+    let template = monster_for_depth(depth);
+    let taken = |id: &str| game.get_components_by_obj(id).is_some_and(|c| !c.is_empty());
+    let obj_id = std::iter::once(template.name.to_string())
+        .chain(std::iter::once(format!("{}{}", template.name, depth)))
+        .chain((2..).map(|n| format!("{}{}-{}", template.name, depth, n)))
+        .find(|id| !taken(id))
+        .unwrap();
+    spawn_monster(game, &template, &obj_id, WorldPosition { x: lair.0, y: lair.1, map });
+    SpawnData { obj: obj_id, kind: "monster".to_string() }.event()
+}
@@ -0,0 +1,190 @@
+use std::{fs, io::{ErrorKind, Write}, path::{Path, PathBuf}};
+
+use anyhow::{Context, Result};
+
+use crate::components::{CombatStats, GameOver, Inventory, Keys, Kills, Levels, MessageLog, Status};
+use crate::export::{self, DumpFormat};
+use crate::game::GameManager;
+use crate::items::carried_label;
+use crate::stats;
+
+// Morgue files: a plain text record of a finished game, like NetHack's
+// dumplogs. The parts about the player are taken while they're still alive
+// to be read (see character_sheet), the rest when the file is written.
+
+// how much of the message log goes in
+pub const MORGUE_MESSAGES: usize = 20;
+
+/// Stats, inventory, kills and the current level, as morgue lines.
+pub fn character_sheet(game: &mut GameManager) -> Vec<String> {
+    let mut lines = vec!["== Character ==".to_string()];
+    if let Some(h) = stats::health(game, "player") {
+        lines.push(format!("HP:       {}/{}", h.current_health, h.max_health));
+    }
+    if let Some(s) = game.get_component_data::<Status>("Status", "player") {
+        lines.push(format!("Level:    {}", s.level));
+        lines.push(format!("Depth:    {}", s.depth));
+    }
+    if game.get_component_data::<CombatStats>("CombatStats", "player").is_some() {
+        let c = stats::combat_stats(game, "player");
+        lines.push(format!("Damage:   {}", c.damage));
+        lines.push(format!("Attack:   {}   Defense: {}", c.attack, c.defense));
+        lines.push(format!("Accuracy: {}   Evasion: {}", c.accuracy, c.evasion));
+    }
+
+    lines.push(String::new());
+    lines.push("== Inventory ==".to_string());
+    let inventory: Inventory = game.get_component_data("Inventory", "player").unwrap_or_default();
+    let mut carried: Vec<String> = inventory.items.iter()
+        .map(|obj| carried_label(game, "player", obj))
+        .collect();
+    if let Some(k) = game.get_component_data::<Keys>("Keys", "player").filter(|k| k.count > 0) {
+        carried.push(format!("{} key{}", k.count, if k.count == 1 { "" } else { "s" }));
+    }
+    if carried.is_empty() {
+        carried.push("Nothing.".to_string());
+    }
+    lines.extend(carried);
+
+    lines.push(String::new());
+    lines.push("== Kills ==".to_string());
+    let kills: Kills = game.get_component_data("Kills", "player").unwrap_or_default();
+    for (victim, count) in &kills.counts {
+        lines.push(format!("{:>4} {}", count, victim));
+    }
+    lines.push(format!("{:>4} in total", kills.counts.values().sum::<u32>()));
+
+    lines.push(String::new());
+    lines.push("== Map ==".to_string());
+    match export::map_buffer(game) {
+        Ok(buffer) => lines.extend(export::format_buffer(&buffer, DumpFormat::Text).lines().map(String::from)),
+        Err(_) => lines.push("No map.".to_string())
+    }
+    lines
+}
+
+/// Ends the game for `cause`, e.g. "killed by the enemy" or "quit", by
+/// putting GameOver on the "game" object. However a game ends it goes
+/// through here, so the morgue always has the player's sheet to work from.
+pub fn end_game(game: &mut GameManager, cause: &str) -> GameOver {
+    let status: Option<Status> = game.get_component_data("Status", "player");
+    let over = GameOver {
+        cause: cause.to_string(),
+        turn: status.as_ref().map_or(0, |s| s.turn),
+        depth: status.as_ref().map_or(1, |s| s.depth),
+        sheet: character_sheet(game)
+    };
+    game.set_component_data(&over, "game");
+    over
+}
+
+/// The whole morgue file for a game which has ended.
+pub fn compose(game: &mut GameManager, over: &GameOver) -> String {
+    let mut cause = over.cause.clone();
+    if let Some(first) = cause.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    let mut lines = vec!["Rusty Rogue morgue file".to_string()];
+    if let Some(levels) = game.get_component_data::<Levels>("Levels", "dungeon") {
+        lines.push(format!("Seed: {}", levels.seed));
+    }
+    lines.push(String::new());
+    lines.push(format!("{} on depth {}, after {} turns.", cause, over.depth, over.turn));
+    lines.push(String::new());
+    lines.extend(over.sheet.iter().cloned());
+
+    lines.push(String::new());
+    lines.push("== Last messages ==".to_string());
+    let log: Option<MessageLog> = game.get_component_data("MessageLog", "log");
+    let messages = log.map(|l| l.messages).unwrap_or_default();
+    lines.extend(messages.iter().skip(messages.len().saturating_sub(MORGUE_MESSAGES)).cloned());
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Writes the morgue into `dir`, making it if need be, and returns the path.
+/// Earlier morgues are never overwritten, even from the same moment.
+pub fn write(dir: &Path, text: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let stem = export::timestamped_stem("morgue");
+    let mut copy = 0;
+    loop {
+        let name = if copy == 0 { format!("{}.txt", stem) } else { format!("{}-{}.txt", stem, copy) };
+        let path = dir.join(name);
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes()).with_context(|| format!("failed to write {}", path.display()))?;
+                return Ok(path)
+            },
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => copy += 1,
+            Err(e) => return Err(e).with_context(|| format!("failed to write {}", path.display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Health, TileMap, WorldPosition, map_object};
+
+    #[test]
+    fn records_the_player_and_their_end() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&TileMap::from_ascii("#####\n#...#\n#####\n").unwrap(), &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&crate::components::Glyph { glyph: '@' }, "player");
+        game.add_component_from_data(&Health { current_health: 0, max_health: 20 }, "player");
+        let mut kills = Kills::default();
+        kills.counts.insert("enemy".to_string(), 2);
+        game.add_component_from_data(&kills, "player");
+        let mut log = MessageLog::new(100);
+        for i in 0..30 {
+            log.push(format!("Message {}", i));
+        }
+        game.add_component_from_data(&log, "log");
+
+        let over = GameOver {
+            cause: "killed by the enemy".to_string(),
+            turn: 42,
+            depth: 1,
+            sheet: character_sheet(&mut game)
+        };
+        // the player is gone by the time the file is written
+        game.remove_object("player");
+        let text = compose(&mut game, &over);
+
+        assert!(text.contains("Killed by the enemy on depth 1, after 42 turns."));
+        assert!(text.contains("HP:       0/20"));
+        assert!(text.contains("Nothing."));
+        assert!(text.contains("   2 enemy\n   2 in total"));
+        assert!(text.contains("█@..█"));
+        assert!(text.contains("Message 10\n") && !text.contains("Message 9\n"));
+        assert!(text.ends_with("Message 29\n"));
+    }
+
+    #[test]
+    fn quitting_ends_the_game_like_dying() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&Status { level: 1, depth: 2, turn: 12 }, "player");
+        game.add_component_from_data(&Health { current_health: 15, max_health: 20 }, "player");
+
+        end_game(&mut game, "quit");
+        let over: GameOver = game.get_component_data("GameOver", "game").unwrap();
+        assert_eq!((over.turn, over.depth), (12, 2));
+        assert!(compose(&mut game, &over).contains("Quit on depth 2, after 12 turns."));
+        assert!(over.sheet.contains(&"HP:       15/20".to_string()));
+    }
+
+    #[test]
+    fn morgues_from_the_same_moment_dont_collide() {
+        let dir = std::env::temp_dir().join(format!("rusty-rogue-morgue-{}", std::process::id()));
+        let first = write(&dir, "first").unwrap();
+        let second = write(&dir, "second").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "second");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     io::{self, Stdout},
-    time::Duration, collections::HashMap, hash::Hash, ptr,
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
@@ -10,15 +10,22 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
-    backend::CrosstermBackend, 
-    widgets::{Paragraph}, 
+    backend::{Backend, CrosstermBackend}, 
+    widgets::{Paragraph, Block, Borders, Clear, Gauge, LineGauge, Sparkline}, 
+    symbols,
+    style::{Style, Color},
     Terminal, 
     Frame,
-    layout::Rect,
-    text::{Line}
+    layout::{Rect, Layout, Direction, Constraint, Alignment},
+    text::{Line, Span}
 };
 
-use crate::components::{Glyph, TileMap, TileType, ScreenPosition, WorldPosition, TextBox};
+use serde::de::DeserializeOwned;
+
+use crate::components::{Glyph, TileMap, ScreenPosition, WorldPosition, TextBox, GaugeBox, SparklineBox, MessageLog, Health, HealthBar, Status, Keys, StatusEffects, GameOver, Item, Carryable, Inventory, InventoryScreen, TargetScreen, Ranged, Equippable, Equipment, Consumable, Explored, Vector2, IsComponent, map_object};
+use crate::items::{carried_label, carried_weight};
+use crate::stats;
+use crate::targeting::{Shot, line_of_fire};
 use crate::game::GameManager;
 
 pub fn clamp(val: u16, min: u16, max: u16) -> u16 {
@@ -50,137 +57,510 @@ pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Re
     terminal.show_cursor().context("unable to show cursor")
 }
 
-pub fn assemble_render(game : &mut GameManager) -> Box<dyn FnMut(&mut Frame<CrosstermBackend<Stdout>>)> {
-    // let objs : HashMap<String, GameObject> = objects.clone();
-    // let map : TileMap = map.clone();
+/// The panes the screen is divided into. Recomputed from the frame size on
+/// every draw so the layout reflows when the terminal is resized.
+pub struct ScreenLayout {
+    pub map: Rect,
+    pub sidebar: Rect,
+    pub log: Rect
+}
+
+pub const SIDEBAR_WIDTH: u16 = 24;
+pub const LOG_HEIGHT: u16 = 8;
+
+pub fn screen_layout(area: Rect) -> ScreenLayout {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(LOG_HEIGHT)].as_ref())
+        .split(area);
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(SIDEBAR_WIDTH)].as_ref())
+        .split(rows[0]);
+
+    ScreenLayout {
+        map: cols[0],
+        sidebar: cols[1],
+        log: rows[1]
+    }
+}
+
+/// Translate a rect relative to the top left of `area` into frame space,
+/// clipping anything which falls outside of it.
+pub fn place_in(rect: Rect, area: Rect) -> Rect {
+    let x = area.x.saturating_add(rect.x);
+    let y = area.y.saturating_add(rect.y);
+    let placed = Rect {
+        x: clamp(x, area.left(), area.right()),
+        y: clamp(y, area.top(), area.bottom()),
+        width: rect.width,
+        height: rect.height
+    };
+    placed.intersection(area)
+}
+
+// Anything which can be drawn on the map or in the sidebar
+enum HudWidget {
+    Text(Paragraph<'static>),
+    Gauge(Gauge<'static>),
+    // ratio of a LineGauge, which can't be cloned
+    HealthBar(f64),
+    Sparkline(Vec<u64>)
+}
+
+fn draw_widget<B: Backend>(frame: &mut Frame<B>, widget: &HudWidget, area: Rect) {
+    match widget {
+        HudWidget::Text(p) => frame.render_widget(p.clone(), area),
+        HudWidget::Gauge(g) => frame.render_widget(g.clone(), area),
+        HudWidget::HealthBar(ratio) => {
+            let bar = LineGauge::default()
+                .gauge_style(Style::default().fg(Color::Red).bg(Color::DarkGray))
+                .line_set(symbols::line::THICK)
+                .ratio(*ratio)
+                .label("");
+            frame.render_widget(bar, area)
+        },
+        HudWidget::Sparkline(data) => frame.render_widget(Sparkline::default().data(data), area)
+    }
+}
+
+pub const HEALTH_BAR_WIDTH: u16 = 3;
+
+/// The world coordinate shown at the top left of the map pane. The camera
+/// keeps `focus` centred, stopping at the map edges; maps smaller than the
+/// view are never scrolled.
+pub fn camera_offset(focus: (u16, u16), map_size: (u16, u16), view: (u16, u16)) -> (u16, u16) {
+    let axis = |focus: u16, map: u16, view: u16| -> u16 {
+        if map <= view { return 0 }
+        clamp(focus.saturating_sub(view / 2), 0, map - view)
+    };
+    (axis(focus.0, map_size.0, view.0), axis(focus.1, map_size.1, view.1))
+}
+
+/// Translate a world space rect into the camera's view, or None if it
+/// starts off the top or left of the screen.
+pub fn world_to_view(rect: Rect, camera: (u16, u16)) -> Option<Rect> {
+    if rect.x < camera.0 || rect.y < camera.1 {
+        return None
+    }
+    Some(Rect { x: rect.x - camera.0, y: rect.y - camera.1, ..rect })
+}
+
+// (position, health) of every entity on the level whose health bar is
+// showing, leaving out those the player can't see, as their glyphs are
+fn health_bars(game: &mut GameManager, level: u16, in_view: Option<&Vec<(u16, u16)>>) -> Vec<(WorldPosition, Health)> {
+    let shown: Vec<String> = game.get_components_by_type("HealthBar")
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|comp| comp.extract_data::<HealthBar>().visible)
+        .map(|comp| comp.obj_id.clone())
+        .collect();
+
+    let mut bars = vec![];
+    for obj in shown {
+        let pos: Option<WorldPosition> = game.get_component_data("WorldPosition", &obj);
+        let health: Option<Health> = game.get_component_data("Health", &obj);
+        if let (Some(pos), Some(health)) = (pos, health) {
+            if pos.map == level && in_view.is_none_or(|v| v.contains(&pos.as_tuple_2())) {
+                bars.push((pos, health));
+            }
+        }
+    }
+    bars
+}
+
+/// Every component of type `c_type` paired with the same object's `pos_type`
+/// component. Objects without a position are skipped.
+fn positioned<T, P>(game: &mut GameManager, c_type: &str, pos_type: &str) -> Vec<(P, T)>
+where T: DeserializeOwned + IsComponent, P: DeserializeOwned + IsComponent
+{
+    let items: Vec<(String, T)> = game.get_components_by_type(c_type)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|comp| (comp.obj_id.clone(), comp.extract_data()))
+        .collect();
+
+    let mut with_pos = vec![];
+    for item in items {
+        let pos: P = match game.get_component_data(pos_type, &item.0) {
+            None => continue,
+            Some(c) => c
+        };
+        with_pos.push((pos, item.1));
+    }
+    with_pos
+}
+
+pub type RenderFn<B> = Box<dyn FnMut(&mut Frame<B>)>;
 
-    let mut widgets: Vec<(Paragraph, Rect)> = vec![];
+/// Build the draw closure for the current game state. This works with any
+/// ratatui backend, so the same code can render into a TestBackend.
+pub fn assemble_render<B: Backend>(game : &mut GameManager) -> RenderFn<B> {
+    // map-space widgets, positioned in world coordinates
+    let mut map_widgets: Vec<(HudWidget, Rect)> = vec![];
 
-    let mut glyphs = {
+    // only the level the player is on is drawn
+    let focus: Option<WorldPosition> = game.get_component_data("WorldPosition", "player");
+    let level = focus.as_ref().map_or(0, |f| f.map);
+
+    let glyphs = {
         let mut glyphy: Vec<(String, Glyph)> = vec![];
-        let glyph_comps = game.get_components_by_type_mut("Glyph").unwrap();
+        let glyph_comps = game.get_components_by_type("Glyph").unwrap_or_default();
         for comp in glyph_comps {
             glyphy.push( (comp.obj_id.clone(), serde_json::from_str(comp.data.as_str()).unwrap()) )
         }
         glyphy
     };
 
-    let mut glyph_positions = {
+    let map: TileMap = game.get_component_data("TileMap", &map_object(level)).unwrap();
+
+    // levels without an Explored are shown in full. On the rest, tiles and
+    // what lies on them are remembered once seen, but anything alive is
+    // only shown while it's in the player's field of view.
+    let explored: Option<Explored> = game.get_component_data("Explored", &map_object(level));
+    let seen = |pos: (u16, u16)| explored.as_ref().is_none_or(|e| e.is_seen(pos));
+    let in_view = match (&explored, &focus) {
+        (Some(_), Some(f)) => Some(map.visible_from(f.as_tuple_2(), stats::sight_radius(game, "player"))),
+        _ => None
+    };
+
+    let glyph_positions = {
         let mut glyph_pos = vec![];
-        for glyph in glyphs {
-            let comp_option = &game.get_components("WorldPosition", &glyph.0);
-            let comps = match (comp_option) {
+        for glyph in under_first(game, glyphs) {
+            let pos_data: WorldPosition = match game.get_component_data("WorldPosition", &glyph.0) {
                 None => continue,
                 Some(c) => c
             };
-            if comps.len() == 0 { continue }
-            let comp = &comps[0];
-            let pos_data: WorldPosition = serde_json::from_str(comp.data.as_str()).unwrap();
+            if pos_data.map != level || !seen(pos_data.as_tuple_2()) {
+                continue;
+            }
+            let alive = game.get_component_data::<Health>("Health", &glyph.0).is_some();
+            if alive && in_view.as_ref().is_some_and(|v| !v.contains(&pos_data.as_tuple_2())) {
+                continue;
+            }
             glyph_pos.push((pos_data, glyph.1));
         }
         glyph_pos
     };
 
-    let map: TileMap = 
-    {
-        let maps = game.get_components_by_type_mut("TileMap").unwrap();
-        serde_json::from_str(&maps[0].data.as_str()).unwrap()
-    };
-
     let map_size = map.get_size();
     let mut text = vec![];    
         
     for y in 0..map_size.1 {
         let mut line = "".to_string(); 
         for x in 0..map_size.0 {
-            let glyph = map.tile_at((x,y));
-
-            let ch = match glyph {
-                TileType::FLOOR => '.',
-                TileType::WALL => '█'
-            };
-
-            line.push(ch);
+            line.push(if seen((x, y)) { map.tile_at((x,y)).glyph() } else { ' ' });
         }
         text.push(Line::from(line));
     }
 
-    let text_boxes = {
-        let mut texty: Vec<(String, TextBox)> = vec![];
-        let text_comps = game.get_components_by_type_mut("TextBox").unwrap();
-        for comp in text_comps {
-            texty.push( (comp.obj_id.clone(), serde_json::from_str(comp.data.as_str()).unwrap()) )
-        }
-        texty
-    };
+    let text_positions: Vec<(ScreenPosition, TextBox)> = positioned(game, "TextBox", "ScreenPosition");
+    let gauge_positions: Vec<(ScreenPosition, GaugeBox)> = positioned(game, "GaugeBox", "ScreenPosition");
+    let sparkline_positions: Vec<(ScreenPosition, SparklineBox)> = positioned(game, "SparklineBox", "ScreenPosition");
 
-    let mut text_positions = {
-        let mut text_pos = vec![];
-        for tb in text_boxes {
-            let comp_option = &game.get_components("ScreenPosition", &tb.0);
-            let comps = match comp_option {
-                None => continue,
-                Some(c) => c
-            };
-            if comps.len() == 0 { continue }
-            let comp = &comps[0];
-            let pos_data: ScreenPosition = serde_json::from_str(comp.data.as_str()).unwrap();
-            text_pos.push((pos_data, tb.1));
+    let status_lines = status_text(game);
+    let messages = {
+        let log_comps = game.get_components_by_type("MessageLog").unwrap_or_default();
+        match log_comps.into_iter().next() {
+            None => vec![],
+            Some(comp) => comp.extract_data::<MessageLog>().messages
         }
-        text_pos
     };
 
+    let health_bars = health_bars(game, level, in_view.as_ref());
+    let inventory = inventory_lines(game);
+    let aim = aim_overlay(game);
+
     // render map
     let grid = Paragraph::new(text);
-    widgets.push((grid, Rect {
-        x: 0,
-        y: 0,
-        width: map_size.0,
-        height: map_size.1
-    }));
+
+    // the line of fire goes under anything standing in it
+    if let Some((shot, _)) = &aim {
+        for pos in &shot.path {
+            map_widgets.push((
+                HudWidget::Text(Paragraph::new(Span::styled("*", Style::default().fg(Color::Yellow)))),
+                Rect::new(pos.0, pos.1, 1, 1)
+            ));
+        }
+    }
+
+    // the cursor shows what's under it, highlighted
+    let cursor = aim.map(|(_, at)| {
+        let under = glyph_positions.iter().rev()
+            .find(|(pos, _)| pos.as_tuple_2() == at)
+            .map_or(if seen(at) { map.tile_at(at).glyph() } else { ' ' }, |(_, g)| g.glyph);
+        let style = Style::default().fg(Color::Black).bg(Color::Yellow);
+        (HudWidget::Text(Paragraph::new(Span::styled(under.to_string(), style))), Rect::new(at.0, at.1, 1, 1))
+    });
 
     // render map objects
     for pos_glyph in glyph_positions {
-        widgets.push((
-            Paragraph::new(pos_glyph.1.glyph.to_string()),
+        map_widgets.push((
+            HudWidget::Text(Paragraph::new(pos_glyph.1.glyph.to_string())),
             Rect::new(pos_glyph.0.x, pos_glyph.0.y, 1, 1)
         ))
     }
 
-    // render UI elements
+    map_widgets.extend(cursor);
+
+    // health bars sit above their owner, or below if it's on the top row
+    for (pos, health) in health_bars {
+        let ratio = if health.max_health == 0 { 0.0 } else {
+            f64::from(health.current_health) / f64::from(health.max_health)
+        };
+        // an empty label still takes up one cell before the line starts
+        let x = pos.x.saturating_sub(HEALTH_BAR_WIDTH / 2 + 1);
+        let y = if pos.y == 0 { 1 } else { pos.y - 1 };
+        map_widgets.push((HudWidget::HealthBar(ratio.clamp(0.0, 1.0)), Rect::new(x, y, HEALTH_BAR_WIDTH + 1, 1)));
+    }
+
+    // UI elements are positioned relative to the sidebar, below the stats
+    let mut sidebar_widgets: Vec<(HudWidget, Rect)> = vec![];
+    let stats_height: u16 = status_lines.len().try_into().unwrap();
+    sidebar_widgets.push((HudWidget::Text(Paragraph::new(status_lines)), Rect::new(0, 0, SIDEBAR_WIDTH, stats_height)));
+    let ui_top = stats_height + 1;
+
     for pos_text in text_positions {
         let text_w: u16 = pos_text.1.value.len().try_into().unwrap();
-        widgets.push((
-            Paragraph::new(pos_text.1.value.clone()),
-            Rect::new(pos_text.0.x, pos_text.0.y, text_w, 1)
+        sidebar_widgets.push((
+            HudWidget::Text(Paragraph::new(pos_text.1.value.clone())),
+            Rect::new(pos_text.0.x, pos_text.0.y + ui_top, text_w, 1)
         ));
     }
 
-    let closure = move |frame : &mut Frame<CrosstermBackend<Stdout>>| {
+    for pos_gauge in gauge_positions {
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(Color::Red).bg(Color::Black))
+            .ratio(pos_gauge.1.ratio)
+            .label(pos_gauge.1.label);
+        sidebar_widgets.push((
+            HudWidget::Gauge(gauge),
+            Rect::new(pos_gauge.0.x, pos_gauge.0.y + ui_top, SIDEBAR_WIDTH, 1)
+        ));
+    }
 
-        // add logic for outputting objects in correct place
+    // sparklines take two rows: the label, then the line itself
+    for pos_spark in sparkline_positions {
+        sidebar_widgets.push((
+            HudWidget::Text(Paragraph::new(pos_spark.1.label)),
+            Rect::new(pos_spark.0.x, pos_spark.0.y + ui_top, SIDEBAR_WIDTH, 1)
+        ));
+        sidebar_widgets.push((
+            HudWidget::Sparkline(pos_spark.1.data),
+            Rect::new(pos_spark.0.x, pos_spark.0.y + ui_top + 1, SIDEBAR_WIDTH, 1)
+        ));
+    }
 
-        for widget in &mut widgets {
-            let f = frame.size();
-            let r_x = clamp(widget.1.x, 0, f.right());
-            let r_y = clamp(widget.1.y, 0, f.bottom());
-            let r = Rect{
-                x: r_x,
-                y: r_y,
-                width: clamp(widget.1.width, 0, f.right() - r_x),
-                height: clamp(widget.1.height, 0, f.bottom() - r_y)
-            };
+    let closure = move |frame : &mut Frame<B>| {
+        let layout = screen_layout(frame.size());
 
-            frame.render_widget(widget.0.clone(),r);
+        let map_block = Block::default().borders(Borders::ALL).title("Map");
+        let map_area = map_block.inner(layout.map);
+        frame.render_widget(map_block, layout.map);
+
+        let focus = focus.as_ref().map_or((0, 0), |f| (f.x, f.y));
+        // ratatui truncates whatever overflows the pane, so translating from
+        // world to camera space is all large maps need
+        let camera = camera_offset(focus, map_size, (map_area.width, map_area.height));
+        frame.render_widget(grid.clone().scroll((camera.1, camera.0)), map_area);
+        for widget in &map_widgets {
+            if let Some(rect) = world_to_view(widget.1, camera) {
+                draw_widget(frame, &widget.0, place_in(rect, map_area));
+            }
+        }
+
+        let sidebar_block = Block::default().borders(Borders::ALL).title("Status");
+        let sidebar_area = sidebar_block.inner(layout.sidebar);
+        frame.render_widget(sidebar_block, layout.sidebar);
+        for widget in &sidebar_widgets {
+            draw_widget(frame, &widget.0, place_in(widget.1, sidebar_area));
+        }
+
+        // only show the newest messages which fit, so the log scrolls
+        let log_block = Block::default().borders(Borders::ALL).title("Messages");
+        let log_area = log_block.inner(layout.log);
+        let shown = messages.len().saturating_sub(usize::from(log_area.height));
+        let lines: Vec<Line> = messages[shown..].iter().map(|m| Line::from(m.clone())).collect();
+        frame.render_widget(Paragraph::new(lines).block(log_block), layout.log);
+
+        // the inventory screen covers the middle of the map
+        if let Some(inventory) = &inventory {
+            let width = clamp(INVENTORY_WIDTH, 0, layout.map.width);
+            let height = clamp(inventory.len() as u16 + 2, 0, layout.map.height);
+            let area = Rect::new(
+                layout.map.x + (layout.map.width - width) / 2,
+                layout.map.y + (layout.map.height - height) / 2,
+                width,
+                height
+            );
+            let lines: Vec<Line> = inventory.iter().map(|l| Line::from(l.clone())).collect();
+            frame.render_widget(Clear, area);
+            frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Inventory")), area);
         }
-        
     };
     
     Box::new(closure)
 }
 
-/// Render the application. This is where you would draw the application UI. This example just
-/// draws a greeting.
+pub const INVENTORY_WIDTH: u16 = 40;
+
+/// What the inventory screen shows, if it's open: the player's items by
+/// letter, or the one they've picked and what can be done with it.
+pub fn inventory_lines(game: &mut GameManager) -> Option<Vec<String>> {
+    let screen: InventoryScreen = game.get_component_data("InventoryScreen", "game")?;
+    let inventory: Inventory = game.get_component_data("Inventory", "player").unwrap_or_default();
+
+    let mut lines = vec![];
+    match screen.selected.filter(|item| inventory.items.contains(item)) {
+        None => {
+            // anything past the last letter can't be picked, so isn't listed
+            for (i, item) in inventory.items.iter().enumerate() {
+                let letter = match Inventory::letter(i) {
+                    None => break,
+                    Some(l) => l
+                };
+                lines.push(format!("{} - {}", letter, carried_label(game, "player", item)));
+            }
+            if lines.is_empty() {
+                lines.push("You aren't carrying anything.".to_string());
+            }
+            lines.push(String::new());
+            lines.push(format!("Weight {}/{}   Slots {}/{}",
+                carried_weight(game, "player"), inventory.max_weight, inventory.items.len(), inventory.slots()));
+            lines.push("a-z - look   Esc - close".to_string());
+        },
+        Some(item) => {
+            lines.push(carried_label(game, "player", &item));
+            if let Some(details) = game.get_component_data::<Item>("Item", &item) {
+                lines.push(details.description);
+            }
+            let equippable: Option<Equippable> = game.get_component_data("Equippable", &item);
+            if let Some(e) = &equippable {
+                let mut slot = e.slot.name().to_string();
+                slot[..1].make_ascii_uppercase();
+                lines.push(format!("{}: {}", slot, e.describe()));
+            }
+            if let Some(c) = game.get_component_data::<Carryable>("Carryable", &item) {
+                lines.push(format!("Weight {}", c.total_weight()));
+            }
+            lines.push(String::new());
+            let equipped = game.get_component_data::<Equipment>("Equipment", "player")
+                .is_some_and(|e| e.slot_of(&item).is_some());
+            let mut actions = vec![];
+            if game.get_component_data::<Consumable>("Consumable", &item).is_some() {
+                actions.push("u - use".to_string());
+            }
+            if equippable.is_some() {
+                actions.push(if equipped { "e - take off" } else { "e - equip" }.to_string());
+            }
+            actions.push("d - drop".to_string());
+            actions.push("Esc - back".to_string());
+            lines.push(actions.join("   "));
+        }
+    }
+    Some(lines)
+}
+
+/// The screen shown once the player has died, over nothing else. `note`
+/// goes under the details, e.g. where the morgue file was saved.
+pub fn assemble_game_over<B: Backend>(over: &GameOver, note: &str) -> RenderFn<B> {
+    let mut cause = over.cause.clone();
+    if let Some(first) = cause.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    let lines = vec![
+        Line::from("You died."),
+        Line::from(""),
+        Line::from(format!("{} on depth {},", cause, over.depth)),
+        Line::from(format!("after {} turns.", over.turn)),
+        Line::from(""),
+        Line::from(note.to_string()),
+        Line::from(""),
+        Line::from("r - play again   q - quit")
+    ];
+
+    let closure = move |frame : &mut Frame<B>| {
+        let area = frame.size();
+        let width = clamp(GAME_OVER_WIDTH, 0, area.width);
+        let height = clamp(lines.len() as u16 + 2, 0, area.height);
+        let centred = Rect::new((area.width - width) / 2, (area.height - height) / 2, width, height);
+        let block = Block::default().borders(Borders::ALL).title("Game Over");
+        let text = Paragraph::new(lines.clone()).block(block).alignment(Alignment::Center);
+        frame.render_widget(text, centred);
+    };
+
+    Box::new(closure)
+}
+
+pub const GAME_OVER_WIDTH: u16 = 40;
+
+/// Items first, so whatever stands on them is drawn over them.
+pub fn under_first(game: &mut GameManager, glyphs: Vec<(String, Glyph)>) -> Vec<(String, Glyph)> {
+    let (mut items, others): (Vec<_>, Vec<_>) = glyphs.into_iter()
+        .partition(|(obj, _)| game.get_component_data::<Item>("Item", obj).is_some());
+    items.extend(others);
+    items
+}
+
+// The line of fire and where the cursor is, while the player is aiming.
+fn aim_overlay(game: &mut GameManager) -> Option<(Shot, (u16, u16))> {
+    let screen: TargetScreen = game.get_component_data("TargetScreen", "game")?;
+    let ranged: Ranged = game.get_component_data("Ranged", &screen.item)?;
+    let shot = line_of_fire(game, "player", (screen.x, screen.y), ranged.range)?;
+    Some((shot, (screen.x, screen.y)))
+}
+
+/// What the sidebar says while the player is aiming: the weapon, what a
+/// shot would hit and the keys.
+pub fn target_lines(game: &mut GameManager) -> Option<Vec<String>> {
+    let screen: TargetScreen = game.get_component_data("TargetScreen", "game")?;
+    let ranged: Ranged = game.get_component_data("Ranged", &screen.item)?;
+    let name = game.get_component_data::<Item>("Item", &screen.item).map_or(screen.item.clone(), |i| i.name);
+    let hit = line_of_fire(game, "player", (screen.x, screen.y), ranged.range).and_then(|s| s.hit);
+
+    Some(vec![
+        format!("Aim:   {}", name),
+        format!("Hits:  {}", hit.as_deref().unwrap_or("nothing")),
+        "".to_string(),
+        format!("Enter - {}", if ranged.thrown { "throw" } else { "fire" }),
+        "Tab - next target".to_string(),
+        "Esc - cancel".to_string()
+    ])
+}
+
+fn status_text(game: &mut GameManager) -> Vec<Line<'static>> {
+    let health = stats::health(game, "player");
+    let status: Option<Status> = game.get_component_data("Status", "player");
+    let keys: Option<Keys> = game.get_component_data("Keys", "player");
+    let effects: StatusEffects = game.get_component_data("StatusEffects", "player").unwrap_or_default();
+
+    let hp = match health {
+        None => "--".to_string(),
+        Some(h) => format!("{}/{}", h.current_health, h.max_health)
+    };
+
+    let mut lines = vec![Line::from(format!("HP:    {}", hp))];
+    if let Some(s) = status {
+        lines.push(Line::from(format!("Level: {}", s.level)));
+        lines.push(Line::from(format!("Depth: {}", s.depth)));
+        lines.push(Line::from(format!("Turn:  {}", s.turn)));
+    }
+    if let Some(k) = keys.filter(|k| k.count > 0) {
+        lines.push(Line::from(format!("Keys:  {}", k.count)));
+    }
+    // with the turns each has left
+    for effect in effects.effects {
+        lines.push(Line::from(format!("{} ({})", effect.kind.indicator(), effect.turns)));
+    }
+    if let Some(aim) = target_lines(game) {
+        lines.push(Line::from(""));
+        lines.extend(aim.into_iter().map(Line::from));
+    }
+    lines
+}
+
+// Render the application. This is where you would draw the application UI. This example just
+// draws a greeting.
 // pub fn render_app(frame: &mut ratatui::Frame<CrosstermBackend<Stdout>>) {
 //     frame.render_widget(greeting, frame.size());
 // }
@@ -196,4 +576,241 @@ pub fn poll() -> Result<KeyCode> {
         }
     }
     Ok(KeyCode::Null)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use crate::components::{Glyph, Health, HealthBar, MessageLog, Status, TileMap, TileType, WorldPosition};
+
+    // A small, fully known game state
+    fn snapshot_game() -> GameManager {
+        let mut game = GameManager::new();
+        let mut map = TileMap::new((6, 4));
+        map.draw_rect(&Rect { x: 0, y: 0, width: 6, height: 4 }, TileType::WALL, false);
+
+        let mut log = MessageLog::new(10);
+        log.push("Welcome to Rusty Rogue!".to_string());
+        log.push("The player hits the enemy.".to_string());
+
+        game.add_component_from_data(&map, &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&Glyph { glyph: '@' }, "player");
+        game.add_component_from_data(&Health { current_health: 7, max_health: 10 }, "player");
+        game.add_component_from_data(&Status { level: 2, depth: 3, turn: 42 }, "player");
+        game.add_component_from_data(&WorldPosition { x: 4, y: 2, map: 0 }, "enemy");
+        game.add_component_from_data(&Glyph { glyph: 'M' }, "enemy");
+        game.add_component_from_data(&log, "log");
+        game
+    }
+
+    fn render(game: &mut GameManager, size: (u16, u16)) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(size.0, size.1)).unwrap();
+        terminal.draw(assemble_render(game)).unwrap();
+        buffer_lines(terminal.backend().buffer())
+    }
+
+    fn buffer_lines(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .map(|y| (area.left()..area.right()).map(|x| buffer.get(x, y).symbol.clone()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn the_log_spans_the_bottom_and_the_sidebar_the_right() {
+        let layout = screen_layout(Rect::new(0, 0, 80, 24));
+        assert_eq!(layout.map, Rect::new(0, 0, 80 - SIDEBAR_WIDTH, 24 - LOG_HEIGHT));
+        assert_eq!(layout.sidebar, Rect::new(80 - SIDEBAR_WIDTH, 0, SIDEBAR_WIDTH, 24 - LOG_HEIGHT));
+        assert_eq!(layout.log, Rect::new(0, 24 - LOG_HEIGHT, 80, LOG_HEIGHT));
+    }
+
+    #[test]
+    fn the_map_gives_way_on_small_terminals() {
+        let layout = screen_layout(Rect::new(0, 0, 20, 6));
+        assert_eq!(layout.map.area(), 0);
+        assert_eq!(layout.log, Rect::new(0, 0, 20, 6));
+    }
+
+    #[test]
+    fn placed_rects_are_clipped_to_their_area() {
+        let area = Rect::new(10, 5, 20, 10);
+        assert_eq!(place_in(Rect::new(2, 3, 4, 1), area), Rect::new(12, 8, 4, 1));
+        assert_eq!(place_in(Rect::new(18, 9, 4, 3), area), Rect::new(28, 14, 2, 1));
+        assert_eq!(place_in(Rect::new(25, 0, 4, 1), area).area(), 0);
+    }
+
+    #[test]
+    fn renders_map_sidebar_and_log() {
+        let mut game = snapshot_game();
+        assert_eq!(render(&mut game, (40, 14)), vec![
+            "┌Map───────────┐┌Status────────────────┐",
+            "│██████        ││HP:    7/10           │",
+            "│█@...█        ││Level: 2              │",
+            "│█...M█        ││Depth: 3              │",
+            "│██████        ││Turn:  42             │",
+            "└──────────────┘└──────────────────────┘",
+            "┌Messages──────────────────────────────┐",
+            "│Welcome to Rusty Rogue!               │",
+            "│The player hits the enemy.            │",
+            "│                                      │",
+            "│                                      │",
+            "│                                      │",
+            "│                                      │",
+            "└──────────────────────────────────────┘",
+        ]);
+    }
+
+    // the snapshot game fully explored, with a wall between the player and
+    // a living enemy
+    fn walled_off_game() -> GameManager {
+        let mut game = snapshot_game();
+        let mut map: TileMap = game.get_component_data("TileMap", &map_object(0)).unwrap();
+        map.set_tile((3, 1), TileType::WALL);
+        map.set_tile((3, 2), TileType::WALL);
+        let mut explored = Explored::new(map.get_size());
+        for x in 0..6 {
+            for y in 0..4 {
+                explored.see((x, y));
+            }
+        }
+        game.set_component_data(&map, &map_object(0));
+        game.add_component_from_data(&explored, &map_object(0));
+        game.add_component_from_data(&Health { current_health: 5, max_health: 5 }, "enemy");
+        game
+    }
+
+    #[test]
+    fn monsters_out_of_view_are_hidden() {
+        let mut game = walled_off_game();
+        assert_eq!(render(&mut game, (40, 14))[3], "│█..█.█        ││Depth: 3              │");
+        game.set_component_data(&WorldPosition { x: 2, y: 2, map: 0 }, "enemy");
+        assert_eq!(render(&mut game, (40, 14))[3], "│█.M█.█        ││Depth: 3              │");
+    }
+
+    #[test]
+    fn health_bars_out_of_view_are_hidden() {
+        let mut game = walled_off_game();
+        game.set_component_data(&Health { current_health: 2, max_health: 5 }, "enemy");
+        game.add_component_from_data(&HealthBar { visible: true }, "enemy");
+
+        assert_eq!(render(&mut game, (40, 14))[0..6], [
+            "┌Map───────────┐┌Status────────────────┐",
+            "│██████        ││HP:    7/10           │",
+            "│█@.█.█        ││Level: 2              │",
+            "│█..█.█        ││Depth: 3              │",
+            "│██████        ││Turn:  42             │",
+            "└──────────────┘└──────────────────────┘",
+        ]);
+    }
+
+    #[test]
+    fn health_bars_come_and_go_with_their_owner() {
+        let mut game = walled_off_game();
+        game.add_component_from_data(&HealthBar { visible: true }, "enemy");
+        let map_pane = |lines: Vec<String>| lines[1..5].iter().map(|l| l[..l.find("││").unwrap()].to_string()).collect::<String>();
+
+        game.set_component_data(&WorldPosition { x: 2, y: 2, map: 0 }, "enemy");
+        let shown = map_pane(render(&mut game, (40, 14)));
+        assert!(shown.contains('M') && shown.contains('━'));
+
+        game.set_component_data(&WorldPosition { x: 4, y: 2, map: 0 }, "enemy");
+        let hidden = map_pane(render(&mut game, (40, 14)));
+        assert!(!hidden.contains('M') && !hidden.contains('━'));
+    }
+
+    #[test]
+    fn camera_follows_player_on_large_maps() {
+        let mut game = snapshot_game();
+        let mut map = TileMap::new((30, 10));
+        map.draw_rect(&Rect { x: 0, y: 0, width: 30, height: 10 }, TileType::WALL, false);
+        game.set_component_data(&map, &map_object(0));
+        game.set_component_data(&WorldPosition { x: 27, y: 7, map: 0 }, "player");
+
+        assert_eq!(render(&mut game, (40, 14))[0..6], [
+            "┌Map───────────┐┌Status────────────────┐",
+            "│.............█││HP:    7/10           │",
+            "│.............█││Level: 2              │",
+            "│...........@.█││Depth: 3              │",
+            "│.............█││Turn:  42             │",
+            "└──────────────┘└──────────────────────┘",
+        ]);
+    }
+
+    #[test]
+    fn shows_health_bar_and_newest_messages() {
+        let mut game = snapshot_game();
+        game.add_component_from_data(&Health { current_health: 1, max_health: 3 }, "enemy");
+        game.add_component_from_data(&HealthBar { visible: true }, "enemy");
+        let mut log = MessageLog::new(10);
+        for i in 0..8 {
+            log.push(format!("Message {}", i));
+        }
+        game.set_component_data(&log, "log");
+
+        assert_eq!(render(&mut game, (40, 14)), vec![
+            "┌Map───────────┐┌Status────────────────┐",
+            "│██████        ││HP:    7/10           │",
+            "│█@.━━━        ││Level: 2              │",
+            "│█...M█        ││Depth: 3              │",
+            "│██████        ││Turn:  42             │",
+            "└──────────────┘└──────────────────────┘",
+            "┌Messages──────────────────────────────┐",
+            "│Message 2                             │",
+            "│Message 3                             │",
+            "│Message 4                             │",
+            "│Message 5                             │",
+            "│Message 6                             │",
+            "│Message 7                             │",
+            "└──────────────────────────────────────┘",
+        ]);
+    }
+
+    #[test]
+    fn game_over_shows_cause_depth_and_turns() {
+        let over = GameOver { cause: "killed by the enemy".to_string(), turn: 42, depth: 3, sheet: vec![] };
+        let mut terminal = Terminal::new(TestBackend::new(44, 12)).unwrap();
+        terminal.draw(assemble_game_over(&over, "Morgue: morgue/morgue-1.txt")).unwrap();
+        assert_eq!(buffer_lines(terminal.backend().buffer()), vec![
+            "                                            ",
+            "  ┌Game Over─────────────────────────────┐  ",
+            "  │               You died.              │  ",
+            "  │                                      │  ",
+            "  │    Killed by the enemy on depth 3,   │  ",
+            "  │            after 42 turns.           │  ",
+            "  │                                      │  ",
+            "  │      Morgue: morgue/morgue-1.txt     │  ",
+            "  │                                      │  ",
+            "  │       r - play again   q - quit      │  ",
+            "  └──────────────────────────────────────┘  ",
+            "                                            ",
+        ]);
+    }
+
+    #[test]
+    fn the_camera_centres_on_the_focus_away_from_the_edges() {
+        assert_eq!(camera_offset((50, 20), (100, 40), (20, 10)), (40, 15));
+    }
+
+    #[test]
+    fn the_camera_stops_at_the_map_edges() {
+        // top left
+        assert_eq!(camera_offset((2, 1), (100, 40), (20, 10)), (0, 0));
+        // bottom right
+        assert_eq!(camera_offset((99, 39), (100, 40), (20, 10)), (80, 30));
+        // a map smaller than the view never scrolls
+        assert_eq!(camera_offset((9, 9), (10, 10), (20, 20)), (0, 0));
+        // a map exactly the size of the view never scrolls
+        assert_eq!(camera_offset((19, 9), (20, 10), (20, 10)), (0, 0));
+    }
+
+    #[test]
+    fn rects_are_moved_into_the_view_or_dropped_off_its_top_left() {
+        let rect = Rect { x: 45, y: 17, width: 3, height: 1 };
+        assert_eq!(world_to_view(rect, (40, 15)), Some(Rect { x: 5, y: 2, width: 3, height: 1 }));
+        assert_eq!(world_to_view(rect, (45, 17)), Some(Rect { x: 0, y: 0, width: 3, height: 1 }));
+        assert_eq!(world_to_view(rect, (46, 15)), None);
+        assert_eq!(world_to_view(rect, (40, 18)), None);
+    }
+}
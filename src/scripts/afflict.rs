@@ -0,0 +1,28 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, EffectData, MessageData};
+use crate::components::StatusEffects;
+
+// Effect: the user gets the item's StatusEffects, stacked onto any it
+// already has.
+pub fn afflict(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: EffectData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.effect != listener.object_id {
+        return vec![]
+    }
+
+    let grants: StatusEffects = match game.get_component_data("StatusEffects", &data.item) {
+        None => return vec![MessageData::event("Nothing seems to happen.".to_string())],
+        Some(c) => c
+    };
+    let mut status: StatusEffects = game.get_component_data("StatusEffects", &data.user).unwrap_or_default();
+    let mut evs = vec![];
+    for effect in grants.effects {
+        status.add(effect);
+        evs.push(MessageData::event(format!("The {} {}.", data.user, effect.kind.started())));
+    }
+    game.set_component_data(&status, &data.user);
+
+    evs.reverse();
+    return evs
+}
@@ -0,0 +1,53 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, InputData, TargetData, MessageData};
+use crossterm::event::KeyCode;
+use crate::components::{WorldPosition, Inventory, Equipment, Ranged, InputContext, TargetScreen};
+use crate::targeting::targets_in_sight;
+
+// 'f' aims the wielded bow and 't' the first thing in the pack for
+// throwing. Aiming opens the targeting cursor on the nearest thing in
+// sight, or the listener itself; see target_keys.
+pub fn aim(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    let item = match data.key_code {
+        KeyCode::Char('f') => {
+            let equipment: Equipment = game.get_component_data("Equipment", &listener.object_id).unwrap_or_default();
+            match equipment.weapon.filter(|w| ranged(game, w).is_some_and(|r| !r.thrown)) {
+                None => return vec![MessageData::event("You have nothing to fire.".to_string())],
+                Some(w) => w
+            }
+        },
+        KeyCode::Char('t') => {
+            let inventory: Inventory = game.get_component_data("Inventory", &listener.object_id).unwrap_or_default();
+            match inventory.items.into_iter().find(|i| ranged(game, i).is_some_and(|r| r.thrown)) {
+                None => return vec![MessageData::event("You have nothing to throw.".to_string())],
+                Some(i) => i
+            }
+        },
+        _ => return vec![]
+    };
+    let position: WorldPosition = match game.get_component_data("WorldPosition", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    let nearest = targets_in_sight(game, &listener.object_id).into_iter().next();
+    let at = nearest.as_ref()
+        .and_then(|t| game.get_component_data::<WorldPosition>("WorldPosition", t))
+        .unwrap_or(position);
+    game.set_component_data(&TargetScreen { item, x: at.x, y: at.y }, "game");
+    game.set_component_data(&InputContext::named("target"), "game");
+
+    match nearest {
+        None => vec![],
+        Some(target) => vec![GameEvent {
+            ev_type: "game.target".to_string(),
+            data: serde_json::to_string(&TargetData { target }).unwrap()
+        }]
+    }
+}
+
+fn ranged(game: &mut GameManager, item: &str) -> Option<Ranged> {
+    game.get_component_data("Ranged", item)
+}
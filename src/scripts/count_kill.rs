@@ -0,0 +1,18 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, DeathData};
+use crate::components::Kills;
+
+// Tallies deaths the listener caused in its Kills.
+pub fn count_kill(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: DeathData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.source != listener.object_id || data.target == listener.object_id {
+        return vec![]
+    }
+
+    let mut kills: Kills = game.get_component_data("Kills", &listener.object_id).unwrap_or_default();
+    *kills.counts.entry(data.target).or_insert(0) += 1;
+    game.set_component_data(&kills, &listener.object_id);
+
+    return vec![]
+}
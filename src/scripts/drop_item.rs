@@ -0,0 +1,41 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, ItemData, MessageData};
+use crate::components::{WorldPosition, Inventory, Equipment};
+use crate::items::item_label;
+use crate::stats::clamp_health;
+
+// Takes an item out of the listener's Inventory, and off if it's equipped,
+// and leaves it where the listener stands.
+pub fn drop_item(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: ItemData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.actor != listener.object_id {
+        return vec![]
+    }
+
+    let mut inventory: Inventory = match game.get_component_data("Inventory", &data.actor) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let index = match inventory.items.iter().position(|i| *i == data.item) {
+        None => return vec![],
+        Some(i) => i
+    };
+    let position: WorldPosition = match game.get_component_data("WorldPosition", &data.actor) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    inventory.items.remove(index);
+    game.set_component_data(&inventory, &data.actor);
+    game.set_component_data(&position, &data.item);
+    if let Some(mut equipment) = game.get_component_data::<Equipment>("Equipment", &data.actor) {
+        if let Some(slot) = equipment.slot_of(&data.item) {
+            equipment.set(slot, None);
+            game.set_component_data(&equipment, &data.actor);
+            clamp_health(game, &data.actor);
+        }
+    }
+
+    return vec![MessageData::event(format!("The {} drops the {}.", data.actor, item_label(game, &data.item)))]
+}
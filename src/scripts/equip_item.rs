@@ -0,0 +1,49 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, ItemData, MessageData};
+use crate::components::{Inventory, Equippable, Equipment};
+use crate::items::item_label;
+use crate::stats::clamp_health;
+
+// game.equip puts an item from the listener's Inventory in its slot,
+// taking off whatever was there; game.unequip takes it off again. The
+// listener's stats follow through stats::combat_stats and stats::health.
+pub fn equip_item(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: ItemData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.actor != listener.object_id {
+        return vec![]
+    }
+    let inventory: Inventory = game.get_component_data("Inventory", &data.actor).unwrap_or_default();
+    if !inventory.items.contains(&data.item) {
+        return vec![]
+    }
+    let label = item_label(game, &data.item);
+    let equippable: Equippable = match game.get_component_data("Equippable", &data.item) {
+        None => return vec![MessageData::event(format!("The {} can't be equipped.", label))],
+        Some(c) => c
+    };
+    let slot = equippable.slot;
+    let mut equipment: Equipment = game.get_component_data("Equipment", &data.actor).unwrap_or_default();
+
+    let mut msgs = vec![];
+    if ev.ev_type == "game.unequip" {
+        if equipment.slot_of(&data.item).is_none() {
+            return vec![]
+        }
+        equipment.set(slot, None);
+        msgs.push(format!("The {} {} the {}.", data.actor, slot.removal_verb(), label));
+    } else {
+        if equipment.slot_of(&data.item).is_some() {
+            return vec![]
+        }
+        if let Some(old) = equipment.set(slot, Some(data.item.clone())) {
+            msgs.push(format!("The {} {} the {}.", data.actor, slot.removal_verb(), item_label(game, &old)));
+        }
+        msgs.push(format!("The {} {} the {}.", data.actor, slot.verb(), label));
+    }
+    game.set_component_data(&equipment, &data.actor);
+    clamp_health(game, &data.actor);
+
+    // events are handled last first
+    return msgs.into_iter().rev().map(MessageData::event).collect()
+}
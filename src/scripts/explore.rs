@@ -0,0 +1,29 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener};
+use crate::components::{WorldPosition, TileMap, Explored, Vector2, map_object};
+use crate::stats;
+
+// Marks everything the listener can see from where it stands as explored,
+// on levels which keep track.
+pub fn explore(game: &mut GameManager, _ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let position: WorldPosition = match game.get_component_data("WorldPosition", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let map_id = map_object(position.map);
+    let (map, mut explored): (TileMap, Explored) = match (
+        game.get_component_data("TileMap", &map_id),
+        game.get_component_data("Explored", &map_id)
+    ) {
+        (Some(map), Some(explored)) => (map, explored),
+        _ => return vec![]
+    };
+
+    let radius = stats::sight_radius(game, &listener.object_id);
+    for pos in map.visible_from(position.as_tuple_2(), radius) {
+        explored.see(pos);
+    }
+    game.set_component_data(&explored, &map_id);
+
+    return vec![]
+}
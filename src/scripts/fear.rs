@@ -0,0 +1,37 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, EffectData, MessageData};
+use crate::components::{WorldPosition, TileMap, Health, Afraid, Vector2, SIGHT_RADIUS, map_object};
+
+// Effect: everything else with health which the user can see is too afraid
+// to attack for the item's power in turns.
+pub fn fear(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: EffectData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.effect != listener.object_id {
+        return vec![]
+    }
+
+    let position: WorldPosition = match game.get_component_data("WorldPosition", &data.user) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let map: TileMap = match game.get_component_data("TileMap", &map_object(position.map)) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    let mut scared = vec![];
+    for pos in map.visible_from(position.as_tuple_2(), SIGHT_RADIUS) {
+        for obj in game.objects_at(position.map, pos).to_vec() {
+            if obj != data.user && game.get_component_data::<Health>("Health", &obj).is_some() {
+                game.set_component_data(&Afraid { turns: data.power }, &obj);
+                scared.push(obj);
+            }
+        }
+    }
+    if scared.is_empty() {
+        return vec![MessageData::event("You hear a distant wail.".to_string())]
+    }
+
+    return scared.into_iter().rev().map(|obj| MessageData::event(format!("The {} turns to flee!", obj))).collect()
+}
@@ -0,0 +1,144 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, FireData, HitData, MessageData};
+use crate::components::{WorldPosition, Inventory, Equipment, Item, Ranged};
+use crate::items::split_one;
+use crate::stats::clamp_health;
+use crate::targeting::line_of_fire;
+
+// game.fire shoots or throws an item in the listener's Inventory along the
+// line of fire. The first thing with health in the way gets a game.on_hit
+// with the item as the weapon. Thrown items land where they stop, one from
+// a stack at a time.
+pub fn fire(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: FireData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.actor != listener.object_id {
+        return vec![]
+    }
+    let mut inventory: Inventory = game.get_component_data("Inventory", &data.actor).unwrap_or_default();
+    if !inventory.items.contains(&data.item) {
+        return vec![]
+    }
+    let ranged: Ranged = match game.get_component_data("Ranged", &data.item) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let position: WorldPosition = match game.get_component_data("WorldPosition", &data.actor) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let shot = match line_of_fire(game, &data.actor, (data.x, data.y), ranged.range) {
+        None => return vec![],
+        Some(s) => s
+    };
+    let name = game.get_component_data::<Item>("Item", &data.item).map_or(data.item.clone(), |i| i.name);
+
+    if ranged.thrown {
+        let thrown = split_one(game, &data.item);
+        if thrown == data.item {
+            // the last one leaves the pack, and the hand if it was wielded
+            inventory.items.retain(|i| *i != data.item);
+            game.set_component_data(&inventory, &data.actor);
+            if let Some(mut equipment) = game.get_component_data::<Equipment>("Equipment", &data.actor) {
+                if let Some(slot) = equipment.slot_of(&data.item) {
+                    equipment.set(slot, None);
+                    game.set_component_data(&equipment, &data.actor);
+                    clamp_health(game, &data.actor);
+                }
+            }
+        }
+        let (x, y) = shot.path.last().copied().unwrap_or((position.x, position.y));
+        game.set_component_data(&WorldPosition { x, y, map: position.map }, &thrown);
+    }
+
+    // events are handled last first
+    let outcome = match shot.hit {
+        None => MessageData::event(format!("The {} hits nothing.", name)),
+        Some(target) => GameEvent {
+            ev_type: "game.on_hit".to_string(),
+            data: serde_json::to_string(&HitData {
+                aggressor: data.actor.clone(),
+                target,
+                weapon: Some(data.item.clone())
+            }).unwrap()
+        }
+    };
+    return vec![outcome, MessageData::event(format!("The {} {} the {}.", data.actor, ranged.verb(), name))]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+    use crate::events::GameEventQueue;
+    use crate::components::{TileMap, Health, CombatStats, Carryable, MessageLog, InputContext, TargetScreen, Hostile, map_object};
+    use crate::items::spawn_item;
+    use crate::rterm::target_lines;
+    use crate::scripts::{aim, target_keys, on_hit, take_damage, log_message};
+    use crate::scripts::testing::{press, item_template};
+
+    #[test]
+    fn throw_at_the_nearest_then_the_next() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&TileMap::from_ascii("########\n#......#\n#......#\n#......#\n########\n").unwrap(), &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&CombatStats { accuracy: 100, ..CombatStats::default() }, "player");
+        for (obj, x) in [("rat", 3), ("bat", 6)] {
+            game.add_component_from_data(&WorldPosition { x, y: 1, map: 0 }, obj);
+            game.add_component_from_data(&Health { current_health: 1, max_health: 1 }, obj);
+            game.add_component_from_data(&Hostile, obj);
+        }
+        spawn_item(&mut game, &item_template("throwing knife").unwrap(), "knives", WorldPosition { x: 1, y: 1, map: 0 });
+        game.remove_component("WorldPosition", "knives");
+        game.set_component_data(&Carryable { weight: 1, count: 2, stacks: true }, "knives");
+        game.add_component_from_data(&Inventory { items: vec!["knives".to_string()], ..Inventory::default() }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", aim));
+        eq.attach_listener(Listener::new(vec!["input.target"], "player", target_keys));
+        eq.attach_listener(Listener::new(vec!["game.fire"], "player", fire));
+        for obj in ["rat", "bat"] {
+            eq.attach_listener(Listener::new(vec!["game.on_hit"], obj, on_hit));
+            eq.attach_listener(Listener::new(vec!["game.damage"], obj, take_damage));
+        }
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+
+        press(&mut game, &mut eq, KeyCode::Char('f'));
+        assert!(game.get_component_data::<TargetScreen>("TargetScreen", "game").is_none());
+
+        // the cursor starts on the rat; Tab moves it to the bat, which the
+        // rat is in the way of, so it's moved down past it
+        press(&mut game, &mut eq, KeyCode::Char('t'));
+        let screen: TargetScreen = game.get_component_data("TargetScreen", "game").unwrap();
+        assert_eq!((screen.x, screen.y), (3, 1));
+        press(&mut game, &mut eq, KeyCode::Tab);
+        assert_eq!(target_lines(&mut game).unwrap()[..2], ["Aim:   throwing knife", "Hits:  rat"]);
+        press(&mut game, &mut eq, KeyCode::Down);
+        press(&mut game, &mut eq, KeyCode::Down);
+        assert_eq!(target_lines(&mut game).unwrap()[1], "Hits:  nothing");
+        press(&mut game, &mut eq, KeyCode::Char('t'));
+        assert!(game.get_component_data::<InputContext>("InputContext", "game").unwrap().turn_taken);
+        assert!(game.get_component_data::<Health>("Health", "rat").is_some());
+        // the one thrown lies where it stopped, the other's still held
+        assert_eq!(game.get_component_data::<Carryable>("Carryable", "knives").unwrap().count, 1);
+        assert_eq!(game.objects_at(0, (6, 3)), ["knives.1"]);
+
+        game.set_component_data(&InputContext::default(), "game");
+        press(&mut game, &mut eq, KeyCode::Char('t'));
+        press(&mut game, &mut eq, KeyCode::Enter);
+        assert!(game.get_component_data::<Health>("Health", "rat").is_none());
+        assert!(game.get_component_data::<Inventory>("Inventory", "player").unwrap().items.is_empty());
+        assert_eq!(game.objects_at(0, (3, 1)), ["knives"]);
+
+        let log: MessageLog = game.get_component_data("MessageLog", "log").unwrap();
+        assert_eq!(log.messages[..4], [
+            "You have nothing to fire.",
+            "The player throws the throwing knife.",
+            "The throwing knife hits nothing.",
+            "The player throws the throwing knife."
+        ]);
+        assert!(log.messages[4].starts_with("The player hits the rat for"));
+        assert_eq!(log.messages[5], "The rat dies.");
+    }
+}
@@ -0,0 +1,39 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, HealData, MessageData};
+use crate::components::Health;
+use crate::stats;
+
+// Applies game.heal to the listener, never past its max_health, with
+// equipment counted when it lowers it. The base Health is never left above
+// its own max.
+pub fn heal(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: HealData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.target != listener.object_id {
+        return vec![]
+    }
+
+    let (mut base, derived): (Health, Health) = match (
+        game.get_component_data("Health", &data.target),
+        stats::health(game, &data.target)
+    ) {
+        (Some(base), Some(derived)) => (base, derived),
+        _ => return vec![]
+    };
+    if base.is_dead() {
+        return vec![]
+    }
+
+    let mut capped = Health { max_health: base.max_health.min(derived.max_health), ..base };
+    let gained = capped.heal(data.amount);
+    if gained == 0 {
+        return vec![]
+    }
+    base.current_health = capped.current_health;
+    game.set_component_data(&base, &data.target);
+
+    if data.quiet {
+        return vec![]
+    }
+    return vec![MessageData::event(format!("The {} recovers {} health.", data.target, gained))]
+}
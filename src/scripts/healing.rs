@@ -0,0 +1,15 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, EffectData, HealData};
+use crate::components::Item;
+
+// Effect: heals the user by the item's power.
+pub fn healing(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: EffectData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.effect != listener.object_id {
+        return vec![]
+    }
+
+    let source = game.get_component_data::<Item>("Item", &data.item).map_or(data.item.clone(), |i| i.name);
+    return vec![HealData { target: data.user, source, amount: data.power, quiet: false }.event()]
+}
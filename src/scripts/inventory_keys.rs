@@ -0,0 +1,154 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, InputData, ItemData};
+use crossterm::event::KeyCode;
+use crate::components::{InputContext, Inventory, InventoryScreen, Equippable, Equipment, Consumable};
+
+// Keys pressed on the inventory screen. On the list a letter picks an item
+// to look at; looking at one, the action keys shown under it act on it and
+// go back to the map. Esc backs out one step at a time.
+pub fn inventory_keys(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    let mut screen: InventoryScreen = match game.get_component_data("InventoryScreen", "game") {
+        None => return vec![],
+        Some(c) => c
+    };
+    let inventory: Inventory = game.get_component_data("Inventory", &listener.object_id).unwrap_or_default();
+
+    let item = match screen.selected.clone() {
+        Some(item) if inventory.items.contains(&item) => item,
+        _ => {
+            match data.key_code {
+                KeyCode::Esc => close(game, false),
+                KeyCode::Char(c) => if let Some(i) = inventory.index_of(c) {
+                    screen.selected = Some(inventory.items[i].clone());
+                    game.set_component_data(&screen, "game");
+                },
+                _ => ()
+            }
+            return vec![]
+        }
+    };
+
+    match data.key_code {
+        KeyCode::Esc => {
+            screen.selected = None;
+            game.set_component_data(&screen, "game");
+            vec![]
+        },
+        KeyCode::Char('d') => {
+            close(game, true);
+            vec![ItemData { actor: listener.object_id.clone(), item }.event("game.drop")]
+        },
+        KeyCode::Char('u') if game.get_component_data::<Consumable>("Consumable", &item).is_some() => {
+            close(game, true);
+            vec![ItemData { actor: listener.object_id.clone(), item }.event("game.use_item")]
+        },
+        KeyCode::Char('e') if game.get_component_data::<Equippable>("Equippable", &item).is_some() => {
+            let equipped = game.get_component_data::<Equipment>("Equipment", &listener.object_id)
+                .is_some_and(|e| e.slot_of(&item).is_some());
+            close(game, true);
+            let ev_type = if equipped { "game.unequip" } else { "game.equip" };
+            vec![ItemData { actor: listener.object_id.clone(), item }.event(ev_type)]
+        },
+        _ => vec![]
+    }
+}
+
+// back to the map, spending a turn if something was done
+fn close(game: &mut GameManager, turn_taken: bool) {
+    game.remove_component("InventoryScreen", "game");
+    game.set_component_data(&InputContext { turn_taken, ..InputContext::default() }, "game");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GameEventQueue;
+    use crate::components::WorldPosition;
+    use crate::items::spawn_item;
+    use crate::rterm::inventory_lines;
+    use crate::scripts::{open_inventory, drop_item, equip_item};
+    use crate::scripts::testing::{press, item_template};
+
+    #[test]
+    fn look_at_equip_and_drop_items() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        for (i, name) in ["dagger", "leather armour"].iter().enumerate() {
+            let item = format!("item{}", i);
+            spawn_item(&mut game, &item_template(name).unwrap(), &item, WorldPosition { x: 1, y: 1, map: 0 });
+            game.remove_component("WorldPosition", &item);
+        }
+        game.add_component_from_data(&Inventory { items: vec!["item0".to_string(), "item1".to_string()], ..Inventory::default() }, "player");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", open_inventory));
+        eq.attach_listener(Listener::new(vec!["input.inventory"], "player", inventory_keys));
+        eq.attach_listener(Listener::new(vec!["game.drop"], "player", drop_item));
+        eq.attach_listener(Listener::new(vec!["game.equip", "game.unequip"], "player", equip_item));
+
+        assert!(inventory_lines(&mut game).is_none());
+        press(&mut game, &mut eq, KeyCode::Char('i'));
+        assert_eq!(inventory_lines(&mut game).unwrap(), [
+            "a - dagger",
+            "b - leather armour",
+            "",
+            "Weight 12/40   Slots 2/26",
+            "a-z - look   Esc - close"
+        ]);
+
+        // no item under z
+        press(&mut game, &mut eq, KeyCode::Char('z'));
+        press(&mut game, &mut eq, KeyCode::Char('b'));
+        assert_eq!(inventory_lines(&mut game).unwrap(), [
+            "leather armour",
+            "Stiff, but better than nothing.",
+            "Armour: +2 defense, -2 evasion",
+            "Weight 10",
+            "",
+            "e - equip   d - drop   Esc - back"
+        ]);
+        press(&mut game, &mut eq, KeyCode::Esc);
+        assert_eq!(inventory_lines(&mut game).unwrap()[0], "a - dagger");
+
+        press(&mut game, &mut eq, KeyCode::Char('b'));
+        press(&mut game, &mut eq, KeyCode::Char('e'));
+        assert!(inventory_lines(&mut game).is_none());
+        press(&mut game, &mut eq, KeyCode::Char('i'));
+        assert_eq!(inventory_lines(&mut game).unwrap()[1], "b - leather armour (armour)");
+        press(&mut game, &mut eq, KeyCode::Char('b'));
+        assert_eq!(inventory_lines(&mut game).unwrap().last().unwrap(), "e - take off   d - drop   Esc - back");
+        press(&mut game, &mut eq, KeyCode::Esc);
+
+        press(&mut game, &mut eq, KeyCode::Char('a'));
+        press(&mut game, &mut eq, KeyCode::Char('d'));
+        assert!(inventory_lines(&mut game).is_none());
+        let context: InputContext = game.get_component_data("InputContext", "game").unwrap();
+        assert!(context.name == "map" && context.turn_taken);
+        assert_eq!(game.get_component_data::<Inventory>("Inventory", "player").unwrap().items, ["item1"]);
+        assert_eq!(game.objects_at(0, (1, 1)), ["player", "item0"]);
+    }
+
+    #[test]
+    fn every_letter_picks_its_item() {
+        let mut game = GameManager::new();
+        let items: Vec<String> = (0..10).map(|i| format!("item{}", i)).collect();
+        for item in &items {
+            spawn_item(&mut game, &item_template("dagger").unwrap(), item, WorldPosition { x: 1, y: 1, map: 0 });
+            game.remove_component("WorldPosition", item);
+        }
+        game.add_component_from_data(&Inventory { items, ..Inventory::default() }, "player");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", open_inventory));
+        eq.attach_listener(Listener::new(vec!["input.inventory"], "player", inventory_keys));
+
+        press(&mut game, &mut eq, KeyCode::Char('i'));
+        // i is the ninth item rather than closing the screen
+        press(&mut game, &mut eq, KeyCode::Char('i'));
+        let screen: InventoryScreen = game.get_component_data("InventoryScreen", "game").unwrap();
+        assert_eq!(screen.selected.as_deref(), Some("item8"));
+        press(&mut game, &mut eq, KeyCode::Esc);
+        press(&mut game, &mut eq, KeyCode::Esc);
+        assert!(inventory_lines(&mut game).is_none());
+    }
+}
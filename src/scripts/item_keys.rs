@@ -0,0 +1,41 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, InputData, ItemData, MessageData};
+use crossterm::event::KeyCode;
+use crate::components::{WorldPosition, Item, Inventory, Vector2};
+use crate::stats;
+
+// 'g' or ',' picks up the top item the listener is standing on and 'D'
+// drops the one it picked up last.
+pub fn item_keys(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    match data.key_code {
+        KeyCode::Char('g') | KeyCode::Char(',') => {
+            let position: WorldPosition = match game.get_component_data("WorldPosition", &listener.object_id) {
+                None => return vec![],
+                Some(c) => c
+            };
+            let here = game.objects_at(position.map, position.as_tuple_2()).to_vec();
+            let item = here.into_iter().rev()
+                .find(|obj| game.get_component_data::<Item>("Item", obj).is_some());
+            match item {
+                None => vec![MessageData::event("There is nothing here to pick up.".to_string())],
+                Some(item) => {
+                    stats::take_turn(game);
+                    vec![ItemData { actor: listener.object_id.clone(), item }.event("game.pick_up")]
+                }
+            }
+        },
+        KeyCode::Char('D') => {
+            let inventory: Inventory = game.get_component_data("Inventory", &listener.object_id).unwrap_or_default();
+            match inventory.items.last() {
+                None => vec![MessageData::event("You have nothing to drop.".to_string())],
+                Some(item) => {
+                    stats::take_turn(game);
+                    vec![ItemData { actor: listener.object_id.clone(), item: item.clone() }.event("game.drop")]
+                }
+            }
+        },
+        _ => vec![]
+    }
+}
@@ -0,0 +1,18 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, MessageData};
+use crate::components::MessageLog;
+
+pub fn log_message(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: MessageData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    let mut log: MessageLog = match game.get_component_data("MessageLog", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    log.push(data.text);
+
+    game.set_component_data(&log, &listener.object_id);
+
+    return vec![]
+}
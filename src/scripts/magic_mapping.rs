@@ -0,0 +1,25 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, EffectData, MessageData};
+use crate::components::{WorldPosition, Explored, map_object};
+
+// Effect: the whole of the user's level becomes explored.
+pub fn magic_mapping(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: EffectData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.effect != listener.object_id {
+        return vec![]
+    }
+
+    let level = match game.get_component_data::<WorldPosition>("WorldPosition", &data.user) {
+        None => return vec![],
+        Some(c) => c.map
+    };
+    let mut explored: Explored = match game.get_component_data("Explored", &map_object(level)) {
+        None => return vec![MessageData::event("Nothing happens.".to_string())],
+        Some(c) => c
+    };
+    explored.see_all();
+    game.set_component_data(&explored, &map_object(level));
+
+    return vec![MessageData::event("A map of the level forms in your mind.".to_string())]
+}
@@ -1,7 +1,89 @@
 pub use on_hit::on_hit;
-pub use player_move::player_move;
-pub use update_health::update_health;
+pub use player_move::{player_move, key_direction};
+pub use update_monitor::update_monitor;
+pub use log_message::log_message;
+pub use update_status::update_status;
+pub use show_health_bar::show_health_bar;
+pub use use_door::use_door;
+pub use use_stairs::use_stairs;
+pub use take_damage::take_damage;
+pub use heal::heal;
+pub use player_death::player_death;
+pub use monster_attack::monster_attack;
+pub use count_kill::count_kill;
+pub use item_keys::item_keys;
+pub use pick_up::pick_up;
+pub use drop_item::drop_item;
+pub use open_inventory::open_inventory;
+pub use inventory_keys::inventory_keys;
+pub use equip_item::equip_item;
+pub use explore::explore;
+pub use use_item::use_item;
+pub use healing::healing;
+pub use teleport::teleport;
+pub use magic_mapping::magic_mapping;
+pub use fear::fear;
+pub use afflict::afflict;
+pub use tick_status::tick_status;
+pub use aim::aim;
+pub use target_keys::target_keys;
+pub use fire::fire;
 
 mod on_hit;
 mod player_move;
-mod update_health;
\ No newline at end of file
+mod update_monitor;
+mod log_message;
+mod update_status;
+mod show_health_bar;
+mod use_door;
+mod use_stairs;
+mod take_damage;
+mod heal;
+mod player_death;
+mod monster_attack;
+mod count_kill;
+mod item_keys;
+mod pick_up;
+mod drop_item;
+mod open_inventory;
+mod inventory_keys;
+mod equip_item;
+mod explore;
+mod use_item;
+mod healing;
+mod teleport;
+mod magic_mapping;
+mod fear;
+mod afflict;
+mod tick_status;
+mod aim;
+mod target_keys;
+mod fire;
+#[cfg(test)]
+mod testing;
+
+use std::collections::HashMap;
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, GameEventQueue};
+
+pub type Script = fn(&mut GameManager, &GameEvent, &Listener) -> Vec<GameEvent>;
+
+/// The effects a Consumable can name, by name. Add to it before calling
+/// attach_effects and items can name the new effect too.
+pub fn effect_scripts() -> HashMap<String, Script> {
+    let mut effects: HashMap<String, Script> = HashMap::new();
+    effects.insert("healing".to_string(), healing);
+    effects.insert("teleport".to_string(), teleport);
+    effects.insert("magic_mapping".to_string(), magic_mapping);
+    effects.insert("fear".to_string(), fear);
+    effects.insert("afflict".to_string(), afflict);
+    effects
+}
+
+/// Each effect listens for game.effect as the object named after it, and
+/// acts only on those meant for it.
+pub fn attach_effects(eq: &mut GameEventQueue, effects: &HashMap<String, Script>) {
+    for (name, script) in effects {
+        eq.attach_listener(Listener::new(vec!["game.effect"], name, *script));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, HitData, MessageData};
+use crate::components::{WorldPosition, Health, Afraid};
+use crate::stats;
+
+// Each turn the listener attacks the player if it's standing next to them,
+// twice if it's hasted, unless it's Afraid, which wears off a turn at a
+// time.
+pub fn monster_attack(game: &mut GameManager, _ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    // the dead don't fight
+    if game.get_component_data::<Health>("Health", &listener.object_id).is_none() {
+        return vec![]
+    }
+    if let Some(mut afraid) = game.get_component_data::<Afraid>("Afraid", &listener.object_id) {
+        afraid.turns = afraid.turns.saturating_sub(1);
+        if afraid.turns == 0 {
+            game.remove_component("Afraid", &listener.object_id);
+            return vec![MessageData::event(format!("The {} regains its courage.", listener.object_id))]
+        }
+        game.set_component_data(&afraid, &listener.object_id);
+        return vec![]
+    }
+    let me: Option<WorldPosition> = game.get_component_data("WorldPosition", &listener.object_id);
+    let player: Option<WorldPosition> = game.get_component_data("WorldPosition", "player");
+    let (me, player) = match (me, player) {
+        (Some(m), Some(p)) => (m, p),
+        _ => return vec![]
+    };
+
+    if me.map != player.map || me.x.abs_diff(player.x) + me.y.abs_diff(player.y) != 1 {
+        return vec![]
+    }
+
+    let blow = GameEvent {
+        ev_type: "game.on_hit".to_string(),
+        data: serde_json::to_string(&HitData {
+            aggressor: listener.object_id.clone(),
+            target: "player".to_string(),
+            weapon: None
+        }).unwrap()
+    };
+    let actions = stats::actions_per_turn(game, &listener.object_id);
+    return (0..actions).map(|_| blow.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{GameEventQueue, TurnData};
+    use crate::components::{StatusEffect, StatusEffects, EffectKind};
+
+    // counts the blows the player takes
+    fn struck(game: &mut GameManager, _ev : &GameEvent, _listener : &Listener) -> Vec<GameEvent> {
+        let mut health: Health = game.get_component_data("Health", "player").unwrap();
+        health.current_health -= 1;
+        game.set_component_data(&health, "player");
+        return vec![]
+    }
+
+    #[test]
+    fn a_hasted_monster_strikes_twice() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&Health { current_health: 10, max_health: 10 }, "player");
+        game.add_component_from_data(&WorldPosition { x: 2, y: 1, map: 0 }, "orc");
+        game.add_component_from_data(&Health { current_health: 10, max_health: 10 }, "orc");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.turn"], "orc", monster_attack));
+        eq.attach_listener(Listener::new(vec!["game.on_hit"], "player", struck));
+        let turn = |game: &mut GameManager, eq: &mut GameEventQueue, turn| eq.trigger_listeners(game, GameEvent {
+            ev_type: "game.turn".to_string(),
+            data: serde_json::to_string(&TurnData { turn }).unwrap()
+        });
+
+        turn(&mut game, &mut eq, 1);
+        assert_eq!(game.get_component_data::<Health>("Health", "player").unwrap().current_health, 9);
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect { kind: EffectKind::Haste, turns: 5, potency: 0 });
+        game.add_component_from_data(&status, "orc");
+        turn(&mut game, &mut eq, 2);
+        assert_eq!(game.get_component_data::<Health>("Health", "player").unwrap().current_health, 7);
+    }
+}
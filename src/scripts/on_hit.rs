@@ -1,8 +1,15 @@
+use rand::Rng;
 use crate::game::GameManager;
-use crate::events::{GameEvent, Listener, InputData, HitData};
-use crossterm::event::KeyCode;
-use crate::components::{WorldPosition, TileMap, TileType, Health};
+use crate::events::{GameEvent, Listener, HitData, MessageData, DamageData, DamageKind};
+use crate::components::Health;
+use crate::stats::{combat_stats, combat_stats_with};
 
+// Resolves an attack on the listener: a roll to hit from the aggressor's
+// accuracy against the listener's evasion, then the aggressor's damage dice
+// plus attack, less the listener's defense, sent on as game.damage.
+// Equipment is counted, and objects without CombatStats fight with the
+// defaults. A weapon fired or thrown is counted in place of the one
+// wielded.
 pub fn on_hit(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
     
     let hit_data : HitData = serde_json::from_str(ev.data.as_str()).unwrap();
@@ -12,25 +19,95 @@ pub fn on_hit(game: &mut GameManager, ev : &GameEvent, listener : &Listener) ->
         return vec![]        
     }
 
-    let mut health: Health = match game.get_component_data("Health", &hit_data.target) {
-        None => return vec![],
-        Some(c) => c
+    // only things with health can be hurt
+    if game.get_component_data::<Health>("Health", &hit_data.target).is_none() {
+        return vec![]
+    }
+
+    let attacker = match &hit_data.weapon {
+        None => combat_stats(game, &hit_data.aggressor),
+        Some(weapon) => combat_stats_with(game, &hit_data.aggressor, weapon)
     };
+    let defender = combat_stats(game, &hit_data.target);
+    let mut rng = game.rng();
 
-    health.current_health -= 1;
+    if rng.gen_range(0..100) >= attacker.hit_chance(&defender) {
+        return vec![MessageData::event(format!("The {} misses the {}.", hit_data.aggressor, hit_data.target))]
+    }
 
-    if health.current_health == 0 {
-        return vec![
-            GameEvent {
-                ev_type: "game.remove_object".to_string(),
-                data: listener.object_id.clone()
-            }
-        ];
+    // bad dice come from a mistake in the data, so say so rather than guess
+    let dice = match attacker.damage_dice() {
+        Ok(d) => d,
+        Err(e) => return vec![MessageData::event(format!("The {} can't hurt anything: {}", hit_data.aggressor, e))]
+    };
+    let roll = dice.roll(&mut rng).saturating_add(attacker.attack).saturating_sub(defender.defense);
+    let damage = u16::try_from(roll.max(0)).unwrap_or(u16::MAX);
+    if damage == 0 {
+        return vec![MessageData::event(format!("The {} hits the {} but does no harm.", hit_data.aggressor, hit_data.target))]
     }
-    
-    let mut components = game.get_components("Health", &hit_data.target).unwrap();
 
-    components[0].data = serde_json::to_string(&health).unwrap();
+    let hit_msg = MessageData::event(format!("The {} hits the {} for {}.", hit_data.aggressor, hit_data.target, damage));
+    let hurt = DamageData {
+        target: hit_data.target.clone(),
+        source: hit_data.aggressor.clone(),
+        amount: damage,
+        kind: DamageKind::Physical,
+        cause: None
+    };
+
+    // the hit is reported before any death it causes
+    return vec![hurt.event(), hit_msg]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GameEventQueue;
+    use crate::components::{CombatStats, GameRng, MessageLog};
+    use crate::scripts::{take_damage, log_message};
+
+    fn setup(attacker: CombatStats, seed: u64) -> (GameManager, GameEventQueue) {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&GameRng { seed, draws: 0 }, "dungeon");
+        game.add_component_from_data(&attacker, "orc");
+        game.add_component_from_data(&CombatStats { defense: 1, ..CombatStats::default() }, "player");
+        game.add_component_from_data(&Health { current_health: 20, max_health: 20 }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.on_hit"], "player", on_hit));
+        eq.attach_listener(Listener::new(vec!["game.damage"], "player", take_damage));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+        (game, eq)
+    }
 
-    return vec![]
-}
\ No newline at end of file
+    fn attack(game: &mut GameManager, eq: &mut GameEventQueue) {
+        let hit = HitData { aggressor: "orc".to_string(), target: "player".to_string(), weapon: None };
+        eq.trigger_listeners(game, GameEvent { ev_type: "game.on_hit".to_string(), data: serde_json::to_string(&hit).unwrap() });
+    }
+
+    fn outcome(game: &mut GameManager) -> (u16, Vec<String>) {
+        let health = game.get_component_data::<Health>("Health", "player").unwrap().current_health;
+        (health, game.get_component_data::<MessageLog>("MessageLog", "log").unwrap().messages)
+    }
+
+    #[test]
+    fn a_hit_rolls_damage_less_defense() {
+        let (mut game, mut eq) = setup(CombatStats { damage: "2d4".to_string(), attack: 2, accuracy: 100, ..CombatStats::default() }, 1);
+        attack(&mut game, &mut eq);
+        assert_eq!(outcome(&mut game), (12, vec!["The orc hits the player for 8.".to_string()]));
+    }
+
+    #[test]
+    fn a_miss_does_nothing() {
+        let (mut game, mut eq) = setup(CombatStats { damage: "2d4".to_string(), accuracy: -100, ..CombatStats::default() }, 1);
+        attack(&mut game, &mut eq);
+        assert_eq!(outcome(&mut game), (20, vec!["The orc misses the player.".to_string()]));
+    }
+
+    #[test]
+    fn bad_dice_are_reported_not_rolled() {
+        let (mut game, mut eq) = setup(CombatStats { damage: "lots".to_string(), accuracy: 100, ..CombatStats::default() }, 1);
+        attack(&mut game, &mut eq);
+        assert_eq!(outcome(&mut game), (20, vec!["The orc can't hurt anything: 'lots' isn't dice, expected something like 2d6+1".to_string()]));
+    }
+}
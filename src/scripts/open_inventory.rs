@@ -0,0 +1,18 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, InputData};
+use crossterm::event::KeyCode;
+use crate::components::{InputContext, InventoryScreen};
+
+// 'i' opens the inventory screen, which takes the keys from then on; see
+// inventory_keys.
+pub fn open_inventory(game: &mut GameManager, ev : &GameEvent, _listener : &Listener) -> Vec<GameEvent> {
+    let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.key_code != KeyCode::Char('i') {
+        return vec![]
+    }
+
+    game.set_component_data(&InventoryScreen::default(), "game");
+    game.set_component_data(&InputContext::named("inventory"), "game");
+    return vec![]
+}
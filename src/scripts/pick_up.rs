@@ -0,0 +1,157 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, ItemData, MessageData};
+use crate::components::{WorldPosition, Item, Carryable, Inventory};
+use crate::items::{item_label, carried_weight};
+
+// Moves an item from the floor under the listener into its Inventory,
+// stacking it with one of the same name if both stack. Items which would
+// take the Inventory over its capacity or weight are left where they are.
+pub fn pick_up(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: ItemData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.actor != listener.object_id {
+        return vec![]
+    }
+
+    let item: Item = match game.get_component_data("Item", &data.item) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let (at, from): (WorldPosition, WorldPosition) = match (
+        game.get_component_data("WorldPosition", &data.actor),
+        game.get_component_data("WorldPosition", &data.item)
+    ) {
+        (Some(at), Some(from)) => (at, from),
+        _ => return vec![]
+    };
+    if (at.x, at.y, at.map) != (from.x, from.y, from.map) {
+        return vec![]
+    }
+    let carryable: Carryable = match game.get_component_data("Carryable", &data.item) {
+        None => return vec![MessageData::event(format!("The {} can't be carried.", item.name))],
+        Some(c) => c
+    };
+
+    let mut inventory: Inventory = game.get_component_data("Inventory", &data.actor).unwrap_or_default();
+    if carried_weight(game, &data.actor).saturating_add(carryable.total_weight()) > inventory.max_weight {
+        return vec![MessageData::event(format!("The {} is too heavy to carry as well.", item.name))]
+    }
+    let label = item_label(game, &data.item);
+
+    let stack = if carryable.stacks {
+        inventory.items.iter().find(|obj| {
+            let same_name = game.get_component_data::<Item>("Item", obj).is_some_and(|i| i.name == item.name);
+            same_name && game.get_component_data::<Carryable>("Carryable", obj).is_some_and(|c| c.stacks)
+        }).cloned()
+    } else {
+        None
+    };
+    match stack {
+        Some(stack) => {
+            let mut merged: Carryable = game.get_component_data("Carryable", &stack).unwrap();
+            merged.count = merged.count.saturating_add(carryable.count);
+            game.set_component_data(&merged, &stack);
+            game.remove_object(&data.item);
+        },
+        None if inventory.items.len() >= inventory.slots() => {
+            return vec![MessageData::event(format!("The {} has no room for the {}.", data.actor, item.name))]
+        },
+        None => {
+            inventory.items.push(data.item.clone());
+            game.set_component_data(&inventory, &data.actor);
+            game.remove_component("WorldPosition", &data.item);
+        }
+    }
+
+    return vec![MessageData::event(format!("The {} picks up the {}.", data.actor, label))]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+    use crate::events::GameEventQueue;
+    use crate::components::{Glyph, MessageLog};
+    use crate::items::spawn_item;
+    use crate::scripts::{item_keys, drop_item, log_message};
+    use crate::scripts::testing::{press, item_template};
+
+    #[test]
+    fn items_go_from_the_floor_to_the_pack_and_back() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&Glyph { glyph: '@' }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        spawn_item(&mut game, &item_template("dagger").unwrap(), "dagger", WorldPosition { x: 1, y: 1, map: 0 });
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", item_keys));
+        eq.attach_listener(Listener::new(vec!["game.pick_up"], "player", pick_up));
+        eq.attach_listener(Listener::new(vec!["game.drop"], "player", drop_item));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+
+        press(&mut game, &mut eq, KeyCode::Char('g'));
+        let inventory: Inventory = game.get_component_data("Inventory", "player").unwrap();
+        assert_eq!(inventory.items, ["dagger"]);
+        assert!(game.get_component_data::<WorldPosition>("WorldPosition", "dagger").is_none());
+        assert_eq!(game.objects_at(0, (1, 1)), ["player"]);
+        press(&mut game, &mut eq, KeyCode::Char('g'));
+
+        game.set_component_data(&WorldPosition { x: 2, y: 1, map: 0 }, "player");
+        press(&mut game, &mut eq, KeyCode::Char('D'));
+        assert!(game.get_component_data::<Inventory>("Inventory", "player").unwrap().items.is_empty());
+        assert_eq!(game.objects_at(0, (2, 1)), ["player", "dagger"]);
+        press(&mut game, &mut eq, KeyCode::Char('D'));
+
+        let log: MessageLog = game.get_component_data("MessageLog", "log").unwrap();
+        assert_eq!(log.messages, [
+            "The player picks up the dagger.",
+            "There is nothing here to pick up.",
+            "The player drops the dagger.",
+            "You have nothing to drop."
+        ]);
+    }
+
+    #[test]
+    fn stacks_merge_and_limits_are_kept() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&Inventory { items: vec![], capacity: 2, max_weight: 11 }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        let here = || WorldPosition { x: 1, y: 1, map: 0 };
+        // dagger, armour, then two potions which stack, and a ring with no room
+        spawn_item(&mut game, &item_template("copper ring").unwrap(), "ring", here());
+        spawn_item(&mut game, &item_template("healing potion").unwrap(), "potion2", here());
+        spawn_item(&mut game, &item_template("healing potion").unwrap(), "potion1", here());
+        spawn_item(&mut game, &item_template("leather armour").unwrap(), "armour", here());
+        spawn_item(&mut game, &item_template("dagger").unwrap(), "dagger", here());
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", item_keys));
+        eq.attach_listener(Listener::new(vec!["game.pick_up"], "player", pick_up));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+
+        press(&mut game, &mut eq, KeyCode::Char('g'));
+        press(&mut game, &mut eq, KeyCode::Char('g'));
+        // too heavy, so pick the next one up from under it by hand
+        let potion = ItemData { actor: "player".to_string(), item: "potion1".to_string() };
+        eq.trigger_listeners(&mut game, potion.event("game.pick_up"));
+        let potion = ItemData { actor: "player".to_string(), item: "potion2".to_string() };
+        eq.trigger_listeners(&mut game, potion.event("game.pick_up"));
+        let ring = ItemData { actor: "player".to_string(), item: "ring".to_string() };
+        eq.trigger_listeners(&mut game, ring.event("game.pick_up"));
+
+        let inventory: Inventory = game.get_component_data("Inventory", "player").unwrap();
+        assert_eq!(inventory.items, ["dagger", "potion1"]);
+        assert_eq!(game.get_component_data::<Carryable>("Carryable", "potion1").unwrap().count, 2);
+        assert!(game.get_component_data::<Item>("Item", "potion2").is_none());
+        assert_eq!(game.objects_at(0, (1, 1)), ["player", "ring", "armour"]);
+
+        let log: MessageLog = game.get_component_data("MessageLog", "log").unwrap();
+        assert_eq!(log.messages, [
+            "The player picks up the dagger.",
+            "The leather armour is too heavy to carry as well.",
+            "The player picks up the healing potion.",
+            "The player picks up the healing potion.",
+            "The player has no room for the copper ring."
+        ]);
+    }
+}
@@ -0,0 +1,22 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, DeathData};
+use crate::morgue;
+
+// Ends the game when the listener (the player) dies: records how on the
+// "game" object and sends game.player_died with the same details. The
+// player is still around to read from at this point, so their character
+// sheet for the morgue file is taken here (see morgue::end_game).
+pub fn player_death(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: DeathData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.target != listener.object_id {
+        return vec![]
+    }
+
+    let over = morgue::end_game(game, &data.cause);
+
+    return vec![GameEvent {
+        ev_type: "game.player_died".to_string(),
+        data: serde_json::to_string(&over).unwrap()
+    }]
+}
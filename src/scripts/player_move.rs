@@ -1,65 +1,256 @@
 use crate::game::GameManager;
-use crate::events::{GameEvent, Listener, InputData, HitData};
+use crate::events::{GameEvent, Listener, InputData, HitData, MessageData, DoorData};
 use crossterm::event::KeyCode;
-use crate::components::{WorldPosition, TileMap, TileType, Vector2};
+use crate::components::{WorldPosition, TileMap, Vector2, Direction, Key, Keys, Item, EffectKind, map_object};
+use crate::stats;
+use rand::Rng;
+
+// how often a confused move goes a random way instead
+const CONFUSED_STUMBLE: f64 = 0.5;
 
 pub fn player_move(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
     let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
     let key = data.key_code;
 
-    let mut position: WorldPosition = game.get_component_data("WorldPosition", &listener.object_id).unwrap();
-    
-    if key == KeyCode::Left || key == KeyCode::Char('a') {
-        position.x -= 1
+    let position: WorldPosition = game.get_component_data("WorldPosition", &listener.object_id).unwrap();
+    let from = position.as_tuple_2();
+
+    let world: TileMap = game.get_component_data("TileMap", &map_object(position.map)).unwrap();
+
+    if key == KeyCode::Char('c') {
+        return close_doors(game, &world, &listener.object_id, &position);
     }
-    else if key == KeyCode::Right || key == KeyCode::Char('d') {
-        position.x += 1
+    
+    let mut dir = match key_direction(key) {
+        None => return vec![],
+        Some(d) => d
+    };
+    if stats::has_effect(game, &listener.object_id, EffectKind::Confusion) {
+        let mut rng = game.rng();
+        if rng.gen_bool(CONFUSED_STUMBLE) {
+            dir = Direction::CARDINAL[rng.gen_range(0..Direction::CARDINAL.len())];
+        }
     }
-    else if key == KeyCode::Up || key == KeyCode::Char('w') {
-        position.y -= 1
+    // the edge of the map blocks like a wall
+    let to = match position.as_point().offset_within(dir, world.get_size()) {
+        None => return vec![],
+        Some(p) => p
+    };
+    let position = WorldPosition { x: to.x, y: to.y, map: position.map };
+
+    // items lie underfoot, so they're stepped over rather than bumped
+    let here = game.objects_at(position.map, position.as_tuple_2()).to_vec();
+    let items: Vec<Item> = here.iter()
+        .filter_map(|obj| game.get_component_data("Item", obj))
+        .collect();
+    let others: Vec<String> = here.into_iter()
+        .filter(|obj| *obj != listener.object_id && game.get_component_data::<Item>("Item", obj).is_none())
+        .collect();
+
+    // anything standing there other than keys blocks the way, even with
+    // keys lying beside it
+    if let Some(other) = others.iter().find(|obj| game.get_component_data::<Key>("Key", obj).is_none()) {
+        // disallow move, but trigger an on_hit
+        let hit = GameEvent {
+            ev_type: "game.on_hit".to_string(),
+            data: serde_json::to_string(
+                &HitData {
+                    aggressor: listener.object_id.clone(),
+                    target: other.clone(),
+                    weapon: None
+                }
+            ).unwrap()
+        };
+
+        stats::take_turn(game);
+        return vec![hit]
     }
-    else if key == KeyCode::Down || key == KeyCode::Char('s') {
-        position.y += 1
+
+    // keys are picked up by walking over them
+    let mut picked_up = vec![];
+    for key in others {
+        let keys: Keys = game.get_component_data("Keys", &listener.object_id).unwrap_or(Keys { count: 0 });
+        game.set_component_data(&Keys { count: keys.count + 1 }, &listener.object_id);
+        picked_up.push(GameEvent {
+            ev_type: "game.remove_object".to_string(),
+            data: key
+        });
+        picked_up.push(MessageData::event(format!("The {} picks up a key.", listener.object_id)));
     }
 
-    let world: TileMap = {
-        let comps = &game.get_components_by_type_mut("TileMap").unwrap();
-        let comp = &comps[0];
-        serde_json::from_str(comp.data.as_str()).unwrap()
+    let tile = match world.get(to) {
+        None => return vec![],
+        Some(t) => t
     };
+    if tile.opened().is_some() {
+        // bumping a door tries to open it, which takes the move
+        stats::take_turn(game);
+        let open = GameEvent {
+            ev_type: "game.open_door".to_string(),
+            data: serde_json::to_string(
+                &DoorData {
+                    actor: listener.object_id.clone(),
+                    x: position.x,
+                    y: position.y,
+                    map: position.map
+                }
+            ).unwrap()
+        };
+        return vec![open]
+    }
+
+    if !tile.is_walkable() {
+        // disallow movement, explaining if the way looks open
+        if tile.is_transparent() {
+            return vec![MessageData::event(format!("There is {} in the way.", tile.description()))]
+        }
+        return vec![]
+    }
+
+    // finally move
+    game.set_component_data(&position, &listener.object_id);
+    stats::take_turn(game);
 
-    {
-        let positions = game.get_components_by_type_mut("WorldPosition").unwrap();
-        for comp in positions {
-            let cur_pos: WorldPosition = serde_json::from_str(comp.data.as_str()).unwrap();
-            if comp.obj_id != listener.object_id && cur_pos.x == position.x && cur_pos.y == position.y {
-                // disallow move, but trigger an on_hit
-                // println!("hit");
-
-                let hit = GameEvent {
-                    ev_type: "game.on_hit".to_string(),
-                    data: serde_json::to_string(
-                        &HitData {
-                            aggressor: listener.object_id.clone(),
-                            target: comp.obj_id.clone()
-                        }
-                    ).unwrap()
-                };
-
-                return vec![hit]
+    // rough ground takes its cost in turns to cross
+    if tile.cost() > 1 {
+        stats::slow_action(game, &listener.object_id, u16::try_from(tile.cost() - 1).unwrap_or(u16::MAX));
+        if Some(tile) != world.get(from) {
+            picked_up.push(MessageData::event(format!("You slow down to cross {}.", tile.description())));
+        }
+    }
+    match items.as_slice() {
+        [] => (),
+        [item] => picked_up.push(MessageData::event(format!("You see a {} here.", item.name))),
+        _ => picked_up.push(MessageData::event("There are several items here.".to_string()))
+    }
+    return picked_up
+}
+
+// the arrows or wasd
+pub fn key_direction(key: KeyCode) -> Option<Direction> {
+    match key {
+        KeyCode::Left | KeyCode::Char('a') => Some(Direction::West),
+        KeyCode::Right | KeyCode::Char('d') => Some(Direction::East),
+        KeyCode::Up | KeyCode::Char('w') => Some(Direction::North),
+        KeyCode::Down | KeyCode::Char('s') => Some(Direction::South),
+        _ => None
+    }
+}
+
+// a close_door for every open door next to the actor
+fn close_doors(game: &mut GameManager, world: &TileMap, actor: &str, position: &WorldPosition) -> Vec<GameEvent> {
+    let mut evs = vec![];
+    for dir in Direction::CARDINAL {
+        let (x, y) = match position.as_point().offset(dir) {
+            None => continue,
+            Some(p) => p.as_tuple()
+        };
+        if world.get((x, y)).and_then(|t| t.closed()).is_some() {
+            evs.push(GameEvent {
+                ev_type: "game.close_door".to_string(),
+                data: serde_json::to_string(
+                    &DoorData { actor: actor.to_string(), x, y, map: position.map }
+                ).unwrap()
+            });
+        }
+    }
+    if evs.is_empty() {
+        return vec![MessageData::event("There is no open door here.".to_string())]
+    }
+    stats::take_turn(game);
+    evs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use crate::events::GameEventQueue;
+    use crate::components::TileType;
+    use crate::scripts::use_door;
+
+    fn key() -> impl Strategy<Value = KeyCode> {
+        proptest::sample::select(vec![
+            KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+            KeyCode::Char('w'), KeyCode::Char('a'), KeyCode::Char('s'), KeyCode::Char('d'),
+            KeyCode::Char('c'), KeyCode::Char('x')
+        ])
+    }
+
+    proptest! {
+        // maps without walls round the edge, so the player can reach it
+        #[test]
+        fn moves_never_leave_the_map(
+            w in 1u16..12,
+            h in 1u16..12,
+            tiles in proptest::collection::vec(proptest::sample::select(TileType::ALL.to_vec()), 144),
+            start: (u16, u16),
+            keys in proptest::collection::vec(key(), 1..60)
+        ) {
+            let mut map = TileMap::new((w, h));
+            for x in 0..w {
+                for y in 0..h {
+                    map.set_tile((x, y), tiles[usize::from(x * 12 + y)]);
+                }
+            }
+            let mut game = GameManager::new();
+            game.add_component_from_data(&map, &map_object(0));
+            game.add_component_from_data(&WorldPosition { x: start.0 % w, y: start.1 % h, map: 0 }, "player");
+
+            let mut eq = GameEventQueue::new();
+            eq.attach_listener(Listener::new(vec!["input.key_press"], "player", player_move));
+            eq.attach_listener(Listener::new(vec!["game.open_door", "game.close_door"], "dungeon", use_door));
+
+            for key_code in keys {
+                eq.trigger_listeners(&mut game, GameEvent {
+                    ev_type: "input.key_press".to_string(),
+                    data: serde_json::to_string(&InputData { key_code }).unwrap()
+                });
+                let pos: WorldPosition = game.get_component_data("WorldPosition", "player").unwrap();
+                prop_assert!(pos.x < w && pos.y < h);
             }
-        }        
+        }
     }
 
-    let mut components = game.get_components("WorldPosition", &listener.object_id).unwrap();
+    #[test]
+    fn wading_takes_more_turns() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&TileMap::from_ascii("#####\n#.~~#\n#####\n").unwrap(), &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", player_move));
 
-    if world.tile_at(position.as_tuple_2()) != TileType::FLOOR {
-        // disallow movement
-        return vec![]
+        let mut turns = vec![];
+        for _ in 0..3 {
+            eq.trigger_listeners(&mut game, GameEvent {
+                ev_type: "input.key_press".to_string(),
+                data: serde_json::to_string(&InputData { key_code: KeyCode::Right }).unwrap()
+            });
+            turns.push(stats::end_action(&mut game, "player"));
+        }
+        // the last move is into the wall
+        assert_eq!(turns, [2, 2, 1]);
     }
 
-    // finally move
-    components[0].data = serde_json::to_string(&position).unwrap();
+    #[test]
+    fn a_monster_on_a_key_is_fought_not_walked_over() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&TileMap::from_ascii("#####\n#...#\n#####\n").unwrap(), &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&WorldPosition { x: 2, y: 1, map: 0 }, "key");
+        game.add_component_from_data(&Key {}, "key");
+        game.add_component_from_data(&WorldPosition { x: 2, y: 1, map: 0 }, "enemy");
+        let listener = Listener::new(vec!["input.key_press"], "player", player_move);
+        let press = GameEvent {
+            ev_type: "input.key_press".to_string(),
+            data: serde_json::to_string(&InputData { key_code: KeyCode::Right }).unwrap()
+        };
 
-    return vec![]
-}
\ No newline at end of file
+        let evs = player_move(&mut game, &press, &listener);
+        assert_eq!(evs.len(), 1);
+        assert_eq!(evs[0].ev_type, "game.on_hit");
+        assert!(evs[0].data.contains("\"target\":\"enemy\""));
+        assert!(game.get_component_data::<Keys>("Keys", "player").is_none());
+        assert_eq!(game.get_component_data::<WorldPosition>("WorldPosition", "player").unwrap().x, 1);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, TargetData};
+use crate::components::HealthBar;
+
+// Reveals the listener's health bar once it's been hit or targeted.
+pub fn show_health_bar(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: TargetData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.target != listener.object_id {
+        return vec![]
+    }
+
+    let bar: HealthBar = match game.get_component_data("HealthBar", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    if !bar.visible {
+        game.set_component_data(&HealthBar { visible: true }, &listener.object_id);
+    }
+
+    return vec![]
+}
@@ -0,0 +1,109 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, DamageData, DeathData, MessageData};
+use crate::components::Health;
+
+// Applies game.damage to the listener. If that kills it, game.death goes
+// out first so others can react while the object is still there, and then
+// it's removed.
+pub fn take_damage(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: DamageData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.target != listener.object_id {
+        return vec![]
+    }
+
+    let mut health: Health = match game.get_component_data("Health", &data.target) {
+        None => return vec![],
+        Some(c) => c
+    };
+    // already dead, e.g. hit twice in one chain of events
+    if health.is_dead() {
+        return vec![]
+    }
+
+    health.damage(data.amount);
+    game.set_component_data(&health, &data.target);
+    if !health.is_dead() {
+        return vec![]
+    }
+
+    let death = DeathData {
+        target: data.target.clone(),
+        source: data.source.clone(),
+        cause: data.cause.clone().unwrap_or(format!("{} by the {}", data.kind.killed(), data.source))
+    };
+    // events are handled last first
+    return vec![
+        GameEvent {
+            ev_type: "game.remove_object".to_string(),
+            data: data.target.clone()
+        },
+        MessageData::event(format!("The {} dies.", data.target)),
+        GameEvent {
+            ev_type: "game.death".to_string(),
+            data: serde_json::to_string(&death).unwrap()
+        }
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{GameEventQueue, DamageKind, HealData};
+    use crate::components::{MessageLog, Equippable, Equipment, EquipSlot};
+    use crate::scripts::{heal, log_message};
+
+    // logs the cause, and whether the dead object was still there to see it
+    fn witness(game: &mut GameManager, ev : &GameEvent, _listener : &Listener) -> Vec<GameEvent> {
+        let data: DeathData = serde_json::from_str(ev.data.as_str()).unwrap();
+        let present = game.get_component_data::<Health>("Health", &data.target).is_some();
+        return vec![MessageData::event(format!("{}, present: {}", data.cause, present))]
+    }
+
+    fn setup() -> (GameManager, GameEventQueue) {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&Health { current_health: 5, max_health: 10 }, "orc");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.damage"], "orc", take_damage));
+        eq.attach_listener(Listener::new(vec!["game.heal"], "orc", heal));
+        eq.attach_listener(Listener::new(vec!["game.death"], "log", witness));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+        (game, eq)
+    }
+
+    fn messages(game: &mut GameManager) -> Vec<String> {
+        game.get_component_data::<MessageLog>("MessageLog", "log").unwrap().messages
+    }
+
+    #[test]
+    fn healing_stops_at_max() {
+        let (mut game, mut eq) = setup();
+        let heal = HealData { target: "orc".to_string(), source: "potion".to_string(), amount: 50, quiet: false };
+        eq.trigger_listeners(&mut game, GameEvent { ev_type: "game.heal".to_string(), data: serde_json::to_string(&heal).unwrap() });
+        assert_eq!(game.get_component_data::<Health>("Health", "orc").unwrap().current_health, 10);
+        assert_eq!(messages(&mut game), ["The orc recovers 5 health."]);
+    }
+
+    #[test]
+    fn healing_never_lifts_the_base_past_its_max() {
+        let (mut game, mut eq) = setup();
+        game.add_component_from_data(&Equippable { max_health: 5, ..Equippable::new(EquipSlot::Ring) }, "ring");
+        game.add_component_from_data(&Equipment { ring: Some("ring".to_string()), ..Equipment::default() }, "orc");
+        let heal = HealData { target: "orc".to_string(), source: "potion".to_string(), amount: 50, quiet: false };
+        eq.trigger_listeners(&mut game, heal.event());
+        assert_eq!(game.get_component_data::<Health>("Health", "orc").unwrap().current_health, 10);
+        assert_eq!(messages(&mut game), ["The orc recovers 5 health."]);
+    }
+
+    #[test]
+    fn overkill_dies_once_and_announces_it_first() {
+        let (mut game, mut eq) = setup();
+        for _ in 0..2 {
+            let hurt = DamageData { target: "orc".to_string(), source: "lava".to_string(), amount: 500, kind: DamageKind::Fire, cause: None };
+            eq.trigger_listeners(&mut game, hurt.event());
+        }
+        assert!(game.get_component_data::<Health>("Health", "orc").is_none());
+        assert_eq!(messages(&mut game), ["burned to death by the lava, present: true", "The orc dies."]);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, InputData, TargetData, FireData};
+use crossterm::event::KeyCode;
+use crate::components::{WorldPosition, TileMap, Point, InputContext, TargetScreen, map_object};
+use crate::scripts::key_direction;
+use crate::targeting::targets_in_sight;
+
+// Keys while aiming. The arrows or wasd move the cursor and Tab jumps it to
+// the next thing in sight. Enter, or the key aiming started with, lets fly,
+// which takes a turn; Esc lowers the weapon.
+pub fn target_keys(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    let mut screen: TargetScreen = match game.get_component_data("TargetScreen", "game") {
+        None => return vec![],
+        Some(c) => c
+    };
+    let position: WorldPosition = match game.get_component_data("WorldPosition", &listener.object_id) {
+        None => {
+            close(game, false);
+            return vec![]
+        },
+        Some(c) => c
+    };
+
+    match data.key_code {
+        KeyCode::Esc => {
+            close(game, false);
+            vec![]
+        },
+        KeyCode::Enter | KeyCode::Char('f') | KeyCode::Char('t') => {
+            close(game, true);
+            vec![FireData { actor: listener.object_id.clone(), item: screen.item, x: screen.x, y: screen.y }.event()]
+        },
+        KeyCode::Tab => {
+            let targets = targets_in_sight(game, &listener.object_id);
+            if targets.is_empty() {
+                return vec![]
+            }
+            // the one after whatever's under the cursor, round to the nearest
+            let under = game.objects_at(position.map, (screen.x, screen.y)).to_vec();
+            let next = match targets.iter().position(|t| under.contains(t)) {
+                None => 0,
+                Some(i) => (i + 1) % targets.len()
+            };
+            let target = targets[next].clone();
+            let at: WorldPosition = match game.get_component_data("WorldPosition", &target) {
+                None => return vec![],
+                Some(c) => c
+            };
+            screen.x = at.x;
+            screen.y = at.y;
+            game.set_component_data(&screen, "game");
+            vec![GameEvent {
+                ev_type: "game.target".to_string(),
+                data: serde_json::to_string(&TargetData { target }).unwrap()
+            }]
+        },
+        key => {
+            let map: Option<TileMap> = game.get_component_data("TileMap", &map_object(position.map));
+            let to = key_direction(key)
+                .zip(map)
+                .and_then(|(dir, map)| Point::from((screen.x, screen.y)).offset_within(dir, map.get_size()));
+            if let Some(to) = to {
+                screen.x = to.x;
+                screen.y = to.y;
+                game.set_component_data(&screen, "game");
+            }
+            vec![]
+        }
+    }
+}
+
+// back to the map, spending a turn if something was fired
+fn close(game: &mut GameManager, turn_taken: bool) {
+    game.remove_component("TargetScreen", "game");
+    game.set_component_data(&InputContext { turn_taken, ..InputContext::default() }, "game");
+}
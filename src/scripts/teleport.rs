@@ -0,0 +1,38 @@
+use rand::Rng;
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, EffectData, MessageData};
+use crate::components::{WorldPosition, TileMap, map_object};
+
+// Effect: moves the user to a random empty tile of its level it could
+// walk on.
+pub fn teleport(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: EffectData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.effect != listener.object_id {
+        return vec![]
+    }
+
+    let mut position: WorldPosition = match game.get_component_data("WorldPosition", &data.user) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let map: TileMap = match game.get_component_data("TileMap", &map_object(position.map)) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let (w, h) = map.get_size();
+    let spots: Vec<(u16, u16)> = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .filter(|pos| map.tile_at(*pos).is_walkable() && game.objects_at(position.map, *pos).is_empty())
+        .collect();
+    if spots.is_empty() {
+        return vec![MessageData::event("Nothing happens.".to_string())]
+    }
+
+    let spot = spots[game.rng().gen_range(0..spots.len())];
+    position.x = spot.0;
+    position.y = spot.1;
+    game.set_component_data(&position, &data.user);
+
+    return vec![MessageData::event(format!("The {} is whisked away.", data.user))]
+}
@@ -0,0 +1,21 @@
+use crossterm::event::KeyCode;
+use crate::game::GameManager;
+use crate::events::{GameEvent, GameEventQueue, InputData};
+use crate::components::InputContext;
+use crate::items::{ItemTemplate, item_templates};
+
+// Helpers for the scripts' tests.
+
+/// Sends a key the way the run loop does: to the map as input.key_press,
+/// or as input.<name> to whichever screen has the InputContext.
+pub fn press(game: &mut GameManager, eq: &mut GameEventQueue, key: KeyCode) {
+    let context: InputContext = game.get_component_data("InputContext", "game").unwrap_or_default();
+    let ev_type = if context.name == "map" { "input.key_press".to_string() } else { format!("input.{}", context.name) };
+    let data = serde_json::to_string(&InputData { key_code: key }).unwrap();
+    eq.trigger_listeners(game, GameEvent { ev_type, data });
+}
+
+/// The built in item of this name, to spawn into a test.
+pub fn item_template(name: &str) -> Option<ItemTemplate> {
+    item_templates().into_iter().find(|t| t.name == name)
+}
@@ -0,0 +1,120 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, DamageData, DamageKind, HealData, MessageData};
+use crate::components::{Health, StatusEffects, EffectKind};
+
+// Each turn the listener's status effects take hold and count down. Poison
+// hurts and regeneration heals by the effect's potency; the rest work
+// wherever they apply, e.g. confusion in player_move.
+pub fn tick_status(game: &mut GameManager, _ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let obj = &listener.object_id;
+    let mut status: StatusEffects = match game.get_component_data("StatusEffects", obj) {
+        None => return vec![],
+        Some(c) => c
+    };
+    if game.get_component_data::<Health>("Health", obj).is_none() {
+        return vec![]
+    }
+
+    // both act on the turn they run out too
+    let poison = status.get(EffectKind::Poison).map(|e| e.potency);
+    let regen = status.get(EffectKind::Regeneration).map(|e| e.potency);
+
+    let mut evs = vec![];
+    let ended = status.tick();
+    if status.effects.is_empty() {
+        game.remove_component("StatusEffects", obj);
+    } else {
+        game.set_component_data(&status, obj);
+    }
+    // events are handled last first, so these come after the poison
+    for effect in ended.iter().rev() {
+        evs.push(MessageData::event(format!("The {} {}.", obj, effect.kind.ended())));
+    }
+
+    if let Some(amount) = poison {
+        evs.push(DamageData {
+            target: obj.clone(),
+            source: "poison".to_string(),
+            amount,
+            kind: DamageKind::Poison,
+            cause: Some(format!("died of {}", EffectKind::Poison.name()))
+        }.event());
+    }
+    // quietly, so the log isn't a line of recovering every turn
+    if let Some(amount) = regen {
+        evs.push(HealData {
+            target: obj.clone(),
+            source: "regeneration".to_string(),
+            amount,
+            quiet: true
+        }.event());
+    }
+    return evs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{GameEventQueue, TurnData};
+    use crate::components::{MessageLog, StatusEffect, GameOver};
+    use crate::scripts::{take_damage, heal, log_message, player_death};
+
+    fn turn(game: &mut GameManager, eq: &mut GameEventQueue, turn: u32) {
+        eq.trigger_listeners(game, GameEvent {
+            ev_type: "game.turn".to_string(),
+            data: serde_json::to_string(&TurnData { turn }).unwrap()
+        });
+    }
+
+    fn hp(game: &mut GameManager) -> u16 {
+        game.get_component_data::<Health>("Health", "player").unwrap().current_health
+    }
+
+    #[test]
+    fn effects_act_each_turn_then_wear_off() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&Health { current_health: 10, max_health: 20 }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect { kind: EffectKind::Poison, turns: 2, potency: 3 });
+        status.add(StatusEffect { kind: EffectKind::Regeneration, turns: 3, potency: 1 });
+        game.add_component_from_data(&status, "player");
+
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.turn"], "player", tick_status));
+        eq.attach_listener(Listener::new(vec!["game.damage"], "player", take_damage));
+        eq.attach_listener(Listener::new(vec!["game.heal"], "player", heal));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+
+        turn(&mut game, &mut eq, 1);
+        assert_eq!(hp(&mut game), 8);
+        turn(&mut game, &mut eq, 2);
+        assert_eq!(hp(&mut game), 6);
+        turn(&mut game, &mut eq, 3);
+        assert_eq!(hp(&mut game), 7);
+        turn(&mut game, &mut eq, 4);
+        assert_eq!(hp(&mut game), 7);
+
+        assert!(game.get_component_data::<StatusEffects>("StatusEffects", "player").is_none());
+        let log: MessageLog = game.get_component_data("MessageLog", "log").unwrap();
+        assert_eq!(log.messages, ["The player is no longer poisoned.", "The player stops regenerating."]);
+    }
+
+    #[test]
+    fn poison_deaths_are_put_down_to_the_poison() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&Health { current_health: 2, max_health: 20 }, "player");
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect { kind: EffectKind::Poison, turns: 5, potency: 3 });
+        game.add_component_from_data(&status, "player");
+
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.turn"], "player", tick_status));
+        eq.attach_listener(Listener::new(vec!["game.damage"], "player", take_damage));
+        eq.attach_listener(Listener::new(vec!["game.death"], "player", player_death));
+
+        turn(&mut game, &mut eq, 1);
+        let over: GameOver = game.get_component_data("GameOver", "game").unwrap();
+        assert_eq!(over.cause, "died of poison");
+    }
+}
@@ -0,0 +1,178 @@
+use serde_json::Value;
+
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener};
+use crate::components::{Monitor, MonitorKind, TextBox, GaugeBox, SparklineBox};
+
+// Look up a binding path of the form object.Component.field.subfield
+fn resolve_path(game: &mut GameManager, path: &str) -> Option<Value> {
+    let mut parts = path.split('.');
+    let obj = parts.next()?;
+    let c_type = parts.next()?;
+
+    let mut value = game.get_component_value(c_type, obj)?;
+    for field in parts {
+        value = match value {
+            Value::Array(mut a) => {
+                let i: usize = field.parse().ok()?;
+                if i >= a.len() { return None }
+                a.swap_remove(i)
+            },
+            Value::Object(mut o) => o.remove(field)?,
+            _ => return None
+        };
+    }
+    Some(value)
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string()
+    }
+}
+
+fn fill_template(template: &str, values: &[(String, Option<Value>)]) -> String {
+    let mut filled = template.to_string();
+    for (name, value) in values {
+        let shown = match value {
+            None => "?".to_string(),
+            Some(v) => value_to_string(v)
+        };
+        filled = filled.replace(&format!("{{{}}}", name), &shown);
+    }
+    filled
+}
+
+fn number(values: &[(String, Option<Value>)], name: &str) -> Option<f64> {
+    values.iter()
+        .find(|v| v.0 == name)
+        .and_then(|v| v.1.as_ref())
+        .and_then(|v| v.as_f64())
+}
+
+pub fn update_monitor(game: &mut GameManager, _ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    // we don't care about the event as it holds no useful info
+    // this should be the component
+
+    let monitor : Monitor = match game.get_component_data("Monitor", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    let values: Vec<(String, Option<Value>)> = monitor.bindings.iter()
+        .map(|b| (b.0.clone(), resolve_path(game, &b.1)))
+        .collect();
+
+    let label = fill_template(&monitor.template, &values);
+    let obj = listener.object_id.as_str();
+
+    match monitor.kind {
+        MonitorKind::Text => {
+            game.set_component_data(&TextBox { value: label }, obj);
+        },
+        MonitorKind::Gauge { value, max } => {
+            let ratio = match (number(&values, &value), number(&values, &max)) {
+                (Some(v), Some(m)) if m > 0.0 => (v / m).clamp(0.0, 1.0),
+                _ => 0.0
+            };
+            game.set_component_data(&GaugeBox { label, ratio }, obj);
+        },
+        MonitorKind::Sparkline { value, length } => {
+            let mut data = match game.get_component_data::<SparklineBox>("SparklineBox", obj) {
+                None => vec![],
+                Some(s) => s.data
+            };
+            if let Some(v) = number(&values, &value) {
+                data.push(v.max(0.0) as u64);
+            }
+            if data.len() > length {
+                data.drain(0..data.len() - length);
+            }
+            game.set_component_data(&SparklineBox { label, data }, obj);
+        }
+    }
+
+    return vec![];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::events::GameEventQueue;
+    use crate::components::{Health, MessageLog, Kills};
+
+    fn setup() -> (GameManager, GameEventQueue) {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&Health { current_health: 5, max_health: 10 }, "player");
+        let mut log = MessageLog::new(10);
+        log.messages = vec!["first".to_string(), "second".to_string()];
+        game.add_component_from_data(&log, "player");
+        let counts = BTreeMap::from([("goblin".to_string(), 3)]);
+        game.add_component_from_data(&Kills { counts }, "player");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.turn"], "hud", update_monitor));
+        (game, eq)
+    }
+
+    fn turn(game: &mut GameManager, eq: &mut GameEventQueue) {
+        eq.trigger_listeners(game, GameEvent { ev_type: "game.turn".to_string(), data: String::new() });
+    }
+
+    #[test]
+    fn paths_reach_nested_fields_and_array_entries() {
+        let (mut game, _) = setup();
+        assert_eq!(resolve_path(&mut game, "player.Health.current_health"), Some(Value::from(5)));
+        assert_eq!(resolve_path(&mut game, "player.Kills.counts.goblin"), Some(Value::from(3)));
+        assert_eq!(resolve_path(&mut game, "player.MessageLog.messages.1"), Some(Value::from("second")));
+    }
+
+    #[test]
+    fn missing_paths_resolve_to_nothing() {
+        let (mut game, _) = setup();
+        assert_eq!(resolve_path(&mut game, "player"), None);
+        assert_eq!(resolve_path(&mut game, "orc.Health.current_health"), None);
+        assert_eq!(resolve_path(&mut game, "player.Mana.current"), None);
+        assert_eq!(resolve_path(&mut game, "player.Health.armour"), None);
+        assert_eq!(resolve_path(&mut game, "player.Kills.counts.troll"), None);
+        assert_eq!(resolve_path(&mut game, "player.MessageLog.messages.2"), None);
+        assert_eq!(resolve_path(&mut game, "player.MessageLog.messages.last"), None);
+        assert_eq!(resolve_path(&mut game, "player.Health.current_health.deep"), None);
+    }
+
+    #[test]
+    fn templates_show_strings_bare_and_missing_values_as_unknown() {
+        let values = vec![
+            ("hp".to_string(), Some(Value::from(5))),
+            ("name".to_string(), Some(Value::from("Rogue"))),
+            ("mp".to_string(), None)
+        ];
+        assert_eq!(fill_template("{name}: {hp} HP, {mp} MP, {hp}!", &values), "Rogue: 5 HP, ? MP, 5!");
+        assert_eq!(fill_template("{unbound}", &values), "{unbound}");
+    }
+
+    #[test]
+    fn text_monitors_fill_their_text_box() {
+        let (mut game, mut eq) = setup();
+        let monitor = Monitor::new("HP {hp}/{max}", MonitorKind::Text)
+            .bind("hp", "player.Health.current_health")
+            .bind("max", "player.Health.max_health");
+        game.add_component_from_data(&monitor, "hud");
+        turn(&mut game, &mut eq);
+        assert_eq!(game.get_component_data::<TextBox>("TextBox", "hud").unwrap().value, "HP 5/10");
+    }
+
+    #[test]
+    fn gauges_are_empty_when_a_binding_is_missing() {
+        let (mut game, mut eq) = setup();
+        let kind = MonitorKind::Gauge { value: "hp".to_string(), max: "max".to_string() };
+        let monitor = Monitor::new("{hp}", kind)
+            .bind("hp", "player.Health.current_health")
+            .bind("max", "player.Health.maximum");
+        game.add_component_from_data(&monitor, "hud");
+        turn(&mut game, &mut eq);
+        let gauge = game.get_component_data::<GaugeBox>("GaugeBox", "hud").unwrap();
+        assert_eq!((gauge.label.as_str(), gauge.ratio), ("5", 0.0));
+    }
+}
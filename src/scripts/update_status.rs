@@ -0,0 +1,18 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, TurnData};
+use crate::components::Status;
+
+pub fn update_status(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: TurnData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    let mut status: Status = match game.get_component_data("Status", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+
+    status.turn = data.turn;
+
+    game.set_component_data(&status, &listener.object_id);
+
+    return vec![]
+}
@@ -0,0 +1,127 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, DoorData, MessageData};
+use crate::components::{TileMap, Keys, map_object};
+
+// Opens (unlocking if need be) or closes a door on any level.
+pub fn use_door(game: &mut GameManager, ev : &GameEvent, _listener : &Listener) -> Vec<GameEvent> {
+    let data: DoorData = serde_json::from_str(ev.data.as_str()).unwrap();
+    let pos = (data.x, data.y);
+    let map_id = map_object(data.map);
+
+    let mut map: TileMap = match game.get_component_data("TileMap", &map_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let tile = match map.get(pos) {
+        None => return vec![],
+        Some(t) => t
+    };
+
+    let msg = if ev.ev_type == "game.open_door" {
+        let opened = match tile.opened() {
+            None => return vec![],
+            Some(t) => t
+        };
+        if tile.is_locked() {
+            let keys: Keys = game.get_component_data("Keys", &data.actor).unwrap_or(Keys { count: 0 });
+            if keys.count == 0 {
+                return vec![MessageData::event("The door is locked.".to_string())]
+            }
+            game.set_component_data(&Keys { count: keys.count - 1 }, &data.actor);
+            map.set_tile(pos, opened);
+            format!("The {} unlocks the door.", data.actor)
+        } else {
+            map.set_tile(pos, opened);
+            format!("The {} opens the door.", data.actor)
+        }
+    } else {
+        let closed = match tile.closed() {
+            None => return vec![],
+            Some(t) => t
+        };
+        if !game.objects_at(data.map, pos).is_empty() {
+            return vec![MessageData::event("Something is in the way.".to_string())]
+        }
+        map.set_tile(pos, closed);
+        format!("The {} closes the door.", data.actor)
+    };
+
+    game.set_component_data(&map, &map_id);
+    return vec![MessageData::event(msg)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GameEventQueue;
+    use crate::components::{MessageLog, TileType, WorldPosition};
+    use crate::scripts::log_message;
+
+    fn setup(door: TileType, keys: u16) -> (GameManager, GameEventQueue) {
+        let mut game = GameManager::new();
+        let mut map = TileMap::new((5, 5));
+        map.set_tile((2, 2), door);
+        game.add_component_from_data(&map, &map_object(0));
+        game.add_component_from_data(&Keys { count: keys }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.open_door", "game.close_door"], "dungeon", use_door));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+        (game, eq)
+    }
+
+    fn try_door(game: &mut GameManager, eq: &mut GameEventQueue, ev_type: &str) {
+        let data = DoorData { actor: "player".to_string(), x: 2, y: 2, map: 0 };
+        eq.trigger_listeners(game, GameEvent { ev_type: ev_type.to_string(), data: serde_json::to_string(&data).unwrap() });
+    }
+
+    fn door(game: &mut GameManager) -> TileType {
+        game.get_component_data::<TileMap>("TileMap", &map_object(0)).unwrap().get((2, 2)).unwrap()
+    }
+
+    fn keys(game: &mut GameManager) -> u16 {
+        game.get_component_data::<Keys>("Keys", "player").unwrap().count
+    }
+
+    fn messages(game: &mut GameManager) -> Vec<String> {
+        game.get_component_data::<MessageLog>("MessageLog", "log").unwrap().messages
+    }
+
+    #[test]
+    fn a_locked_door_stays_shut_without_a_key() {
+        let (mut game, mut eq) = setup(TileType::LOCKED_DOOR, 0);
+        try_door(&mut game, &mut eq, "game.open_door");
+        assert_eq!(door(&mut game), TileType::LOCKED_DOOR);
+        assert_eq!(messages(&mut game), ["The door is locked."]);
+    }
+
+    #[test]
+    fn a_key_unlocks_the_door_and_is_used_up() {
+        let (mut game, mut eq) = setup(TileType::LOCKED_DOOR, 2);
+        try_door(&mut game, &mut eq, "game.open_door");
+        assert_eq!(door(&mut game), TileType::OPEN_DOOR);
+        assert_eq!(keys(&mut game), 1);
+        assert_eq!(messages(&mut game), ["The player unlocks the door."]);
+    }
+
+    #[test]
+    fn doors_open_and_close_again() {
+        let (mut game, mut eq) = setup(TileType::DOOR, 1);
+        try_door(&mut game, &mut eq, "game.open_door");
+        assert_eq!(door(&mut game), TileType::OPEN_DOOR);
+        try_door(&mut game, &mut eq, "game.close_door");
+        assert_eq!(door(&mut game), TileType::DOOR);
+        // plain doors don't need the key
+        assert_eq!(keys(&mut game), 1);
+        assert_eq!(messages(&mut game), ["The player opens the door.", "The player closes the door."]);
+    }
+
+    #[test]
+    fn doors_dont_close_on_whatever_is_in_the_way() {
+        let (mut game, mut eq) = setup(TileType::OPEN_DOOR, 0);
+        game.add_component_from_data(&WorldPosition { x: 2, y: 2, map: 0 }, "enemy");
+        try_door(&mut game, &mut eq, "game.close_door");
+        assert_eq!(door(&mut game), TileType::OPEN_DOOR);
+        assert_eq!(messages(&mut game), ["Something is in the way."]);
+    }
+}
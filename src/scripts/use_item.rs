@@ -0,0 +1,146 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, ItemData, EffectData, MessageData};
+use crate::components::{Inventory, Item, Carryable, Consumable};
+use crate::items::item_label;
+
+// game.use_item sends a game.effect to the effect script of a Consumable in
+// the listener's Inventory, after the message saying it was used. Once the
+// effect has played out a game.use_up takes one of it away, so the effect
+// can still look at the item.
+pub fn use_item(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: ItemData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    if data.actor != listener.object_id {
+        return vec![]
+    }
+    let mut inventory: Inventory = game.get_component_data("Inventory", &data.actor).unwrap_or_default();
+    if !inventory.items.contains(&data.item) {
+        return vec![]
+    }
+
+    if ev.ev_type == "game.use_up" {
+        // one from a stack, or the whole item
+        match game.get_component_data::<Carryable>("Carryable", &data.item) {
+            Some(mut c) if c.count > 1 => {
+                c.count -= 1;
+                game.set_component_data(&c, &data.item);
+            },
+            _ => {
+                inventory.items.retain(|i| *i != data.item);
+                game.set_component_data(&inventory, &data.actor);
+                game.remove_object(&data.item);
+            }
+        }
+        return vec![]
+    }
+
+    let label = item_label(game, &data.item);
+    let consumable: Consumable = match game.get_component_data("Consumable", &data.item) {
+        None => return vec![MessageData::event(format!("The {} can't be used.", label))],
+        Some(c) => c
+    };
+    let name = game.get_component_data::<Item>("Item", &data.item).map_or(label, |i| i.name);
+    let effect = EffectData {
+        effect: consumable.script,
+        user: data.actor.clone(),
+        item: data.item.clone(),
+        power: consumable.power
+    };
+
+    // events are handled last first
+    return vec![
+        ItemData { actor: data.actor.clone(), item: data.item.clone() }.event("game.use_up"),
+        effect.event(),
+        MessageData::event(format!("The {} {} the {}.", data.actor, consumable.verb, name))
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GameEventQueue;
+    use crate::components::{Health, MessageLog, TileMap, WorldPosition, Explored, StatusEffects, StatusEffect, EffectKind, map_object};
+    use crate::items::spawn_item;
+    use crate::scripts::testing::item_template;
+    use crate::scripts::{heal, log_message, effect_scripts, attach_effects};
+
+    fn use_it(game: &mut GameManager, eq: &mut GameEventQueue, item: &str) {
+        eq.trigger_listeners(game, ItemData { actor: "player".to_string(), item: item.to_string() }.event("game.use_item"));
+    }
+
+    #[test]
+    fn effects_run_and_use_up_the_item() {
+        let mut game = GameManager::new();
+        let map = TileMap::from_ascii("#####\n#...#\n#####\n").unwrap();
+        game.add_component_from_data(&Explored::new(map.get_size()), &map_object(0));
+        game.add_component_from_data(&map, &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&Health { current_health: 5, max_health: 20 }, "player");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        for (obj, name) in [("potions", "healing potion"), ("map", "scroll of magic mapping"), ("dagger", "dagger")] {
+            spawn_item(&mut game, &item_template(name).unwrap(), obj, WorldPosition { x: 1, y: 1, map: 0 });
+            game.remove_component("WorldPosition", obj);
+        }
+        game.set_component_data(&Carryable { weight: 1, count: 2, stacks: true }, "potions");
+        let items = ["potions", "map", "dagger"].map(String::from).to_vec();
+        game.add_component_from_data(&Inventory { items, ..Inventory::default() }, "player");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.use_item", "game.use_up"], "player", use_item));
+        attach_effects(&mut eq, &effect_scripts());
+        eq.attach_listener(Listener::new(vec!["game.heal"], "player", heal));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+
+        use_it(&mut game, &mut eq, "potions");
+        assert_eq!(game.get_component_data::<Health>("Health", "player").unwrap().current_health, 13);
+        assert_eq!(game.get_component_data::<Carryable>("Carryable", "potions").unwrap().count, 1);
+
+        use_it(&mut game, &mut eq, "map");
+        assert!(game.get_component_data::<Explored>("Explored", &map_object(0)).unwrap().is_seen((4, 2)));
+        assert!(game.get_component_data::<Item>("Item", "map").is_none());
+
+        use_it(&mut game, &mut eq, "dagger");
+        let inventory: Inventory = game.get_component_data("Inventory", "player").unwrap();
+        assert_eq!(inventory.items, ["potions", "dagger"]);
+
+        let log: MessageLog = game.get_component_data("MessageLog", "log").unwrap();
+        assert_eq!(log.messages, [
+            "The player drinks the healing potion.",
+            "The player recovers 8 health.",
+            "The player reads the scroll of magic mapping.",
+            "A map of the level forms in your mind.",
+            "The dagger can't be used."
+        ]);
+    }
+
+    #[test]
+    fn potions_stack_their_effects_on_the_drinker() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        spawn_item(&mut game, &item_template("murky potion").unwrap(), "murky", WorldPosition { x: 1, y: 1, map: 0 });
+        game.remove_component("WorldPosition", "murky");
+        game.set_component_data(&Carryable { weight: 1, count: 2, stacks: true }, "murky");
+        game.add_component_from_data(&Inventory { items: vec!["murky".to_string()], ..Inventory::default() }, "player");
+        let mut eq = GameEventQueue::new();
+        eq.attach_listener(Listener::new(vec!["game.use_item", "game.use_up"], "player", use_item));
+        attach_effects(&mut eq, &effect_scripts());
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+
+        use_it(&mut game, &mut eq, "murky");
+        use_it(&mut game, &mut eq, "murky");
+        let status: StatusEffects = game.get_component_data("StatusEffects", "player").unwrap();
+        assert_eq!(status.effects, [StatusEffect { kind: EffectKind::Poison, turns: 5, potency: 4 }]);
+
+        let log: MessageLog = game.get_component_data("MessageLog", "log").unwrap();
+        assert_eq!(log.messages[..2], ["The player drinks the murky potion.", "The player is poisoned."]);
+    }
+
+    #[test]
+    fn every_consumable_has_a_registered_script() {
+        let effects = effect_scripts();
+        for template in crate::items::item_templates() {
+            if let Some((script, _, _)) = template.consume {
+                assert!(effects.contains_key(script), "{} has no script", template.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::game::GameManager;
+use crate::events::{GameEvent, Listener, InputData, MessageData};
+use crossterm::event::KeyCode;
+use crate::components::{WorldPosition, TileMap, TileType, Vector2, Levels, Status, Explored, map_object};
+use crate::mapgen::{generate_level, Prefab};
+use crate::items::{place_items, item_spots};
+use crate::monsters::place_monster;
+use crate::stats;
+
+// '>' and '<' take the listener down or up the stairs it's standing on.
+// Levels are generated the first time anyone goes down to them.
+pub fn use_stairs(game: &mut GameManager, ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+    let data: InputData = serde_json::from_str(ev.data.as_str()).unwrap();
+
+    let (stairs, arrive_on) = match data.key_code {
+        KeyCode::Char('>') => (TileType::STAIRS_DOWN, TileType::STAIRS_UP),
+        KeyCode::Char('<') => (TileType::STAIRS_UP, TileType::STAIRS_DOWN),
+        _ => return vec![]
+    };
+
+    let mut position: WorldPosition = match game.get_component_data("WorldPosition", &listener.object_id) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let here: TileMap = match game.get_component_data("TileMap", &map_object(position.map)) {
+        None => return vec![],
+        Some(c) => c
+    };
+    if here.get(position.as_point()) != Some(stairs) {
+        let way = if stairs == TileType::STAIRS_DOWN { "down" } else { "up" };
+        return vec![MessageData::event(format!("You can't go {} here.", way))]
+    }
+
+    let target = match stairs {
+        TileType::STAIRS_DOWN => position.map + 1,
+        // the first level can be hand written with stairs up to nowhere
+        _ if position.map == 0 => return vec![MessageData::event("The way up is blocked.".to_string())],
+        _ => position.map - 1
+    };
+    let depth = target + 1;
+    let mut evs = vec![];
+    let map: TileMap = match game.get_component_data("TileMap", &map_object(target)) {
+        Some(c) => c,
+        None => {
+            let levels: Levels = match game.get_component_data("Levels", "dungeon") {
+                None => return vec![],
+                Some(c) => c
+            };
+            let vaults = match Prefab::parse(&levels.vaults) {
+                Err(e) => return vec![MessageData::event(format!("The stairs lead nowhere: {}", e))],
+                Ok(v) => v
+            };
+            let dungeon = match generate_level(&levels.map_style, levels.seed, depth, &vaults, &levels.style) {
+                None => return vec![MessageData::event("The stairs lead nowhere.".to_string())],
+                Some(d) => d
+            };
+            game.add_component_from_data(&dungeon.map, &map_object(target));
+            game.add_component_from_data(&Explored::new(dungeon.map.get_size()), &map_object(target));
+            place_items(game, target, &item_spots(&dungeon.item_spawns, &dungeon.spawns));
+            // the furthest spawn is the lair, as on the first level
+            if let Some(lair) = dungeon.spawns.first() {
+                evs.push(place_monster(game, target, depth, *lair));
+            }
+            // and any vault that sets a place aside for one gets one too
+            for spot in &dungeon.monster_spawns {
+                evs.push(place_monster(game, target, depth, *spot));
+            }
+            dungeon.map
+        }
+    };
+
+    // hand written levels might not have stairs back
+    let (x, y) = match map.find(arrive_on).or(map.find(TileType::FLOOR)) {
+        None => return vec![],
+        Some(p) => p
+    };
+    position.x = x;
+    position.y = y;
+    position.map = target;
+    game.set_component_data(&position, &listener.object_id);
+    stats::take_turn(game);
+
+    if let Some(mut status) = game.get_component_data::<Status>("Status", &listener.object_id) {
+        status.depth = depth;
+        game.set_component_data(&status, &listener.object_id);
+    }
+
+    let way = if stairs == TileType::STAIRS_DOWN { "descend" } else { "climb" };
+    evs.push(MessageData::event(format!("You {} to depth {}.", way, depth)));
+    return evs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{GameEventQueue, TurnData};
+    use crate::components::{MessageLog, Hostile};
+    use crate::mapgen::StyleOptions;
+    use crate::scripts::log_message;
+
+    fn roar(_game: &mut GameManager, _ev : &GameEvent, listener : &Listener) -> Vec<GameEvent> {
+        return vec![MessageData::event(format!("The {} roars.", listener.object_id))]
+    }
+
+    fn setup(vaults: &str) -> (GameManager, GameEventQueue) {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&TileMap::from_ascii("#####\n#.>.#\n#####\n").unwrap(), &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 2, y: 1, map: 0 }, "player");
+        game.add_component_from_data(&Status { level: 1, depth: 1, turn: 0 }, "player");
+        game.add_component_from_data(&Levels {
+            seed: 7,
+            map_style: "rooms".to_string(),
+            style: StyleOptions::default(),
+            vaults: vaults.to_string()
+        }, "dungeon");
+        game.add_component_from_data(&MessageLog::new(10), "log");
+        let mut eq = GameEventQueue::new();
+        eq.define_kind("monster", vec![Listener::new(vec!["game.turn"], "", roar)]);
+        eq.attach_listener(Listener::new(vec!["input.key_press"], "player", use_stairs));
+        eq.attach_listener(Listener::new(vec!["game.message"], "log", log_message));
+        (game, eq)
+    }
+
+    fn press(game: &mut GameManager, eq: &mut GameEventQueue, key: char) -> WorldPosition {
+        eq.trigger_listeners(game, GameEvent {
+            ev_type: "input.key_press".to_string(),
+            data: serde_json::to_string(&InputData { key_code: KeyCode::Char(key) }).unwrap()
+        });
+        game.get_component_data("WorldPosition", "player").unwrap()
+    }
+
+    fn messages(game: &mut GameManager) -> Vec<String> {
+        game.get_component_data::<MessageLog>("MessageLog", "log").unwrap().messages
+    }
+
+    #[test]
+    fn stairs_go_down_to_a_new_level_with_a_monster_and_back() {
+        let (mut game, mut eq) = setup("");
+        let below = press(&mut game, &mut eq, '>');
+        assert_eq!(below.map, 1);
+        let level: TileMap = game.get_component_data("TileMap", &map_object(1)).unwrap();
+        assert_eq!(level.get(below.as_point()), Some(TileType::STAIRS_UP));
+        assert_eq!(game.get_component_data::<Status>("Status", "player").unwrap().depth, 2);
+
+        // the level's monster was given its listeners as it was made
+        let goblin: WorldPosition = game.get_component_data("WorldPosition", "goblin").unwrap();
+        assert_eq!(goblin.map, 1);
+        eq.trigger_listeners(&mut game, GameEvent {
+            ev_type: "game.turn".to_string(),
+            data: serde_json::to_string(&TurnData { turn: 1 }).unwrap()
+        });
+        assert_eq!(messages(&mut game), ["You descend to depth 2.", "The goblin roars."]);
+
+        let back = press(&mut game, &mut eq, '<');
+        assert_eq!((back.as_tuple_2(), back.map), ((2, 1), 0));
+        assert_eq!(game.get_component_data::<Status>("Status", "player").unwrap().depth, 1);
+        // going back down finds the same level, not a new one
+        let again = press(&mut game, &mut eq, '>');
+        assert_eq!((again.as_tuple_2(), again.map), (below.as_tuple_2(), 1));
+        assert!(game.get_component_data::<WorldPosition>("WorldPosition", "goblin2").is_none());
+    }
+
+    #[test]
+    fn vaults_set_aside_for_monsters_get_them() {
+        let vault = "#####\n#.M.#\n#####\n";
+        let (mut game, mut eq) = setup(vault);
+        let expected = generate_level("rooms", 7, 2, &Prefab::parse(vault).unwrap(), &StyleOptions::default()).unwrap();
+        assert!(!expected.monster_spawns.is_empty());
+
+        press(&mut game, &mut eq, '>');
+        for spot in expected.monster_spawns {
+            let there: Vec<String> = game.objects_at(1, spot).to_vec();
+            assert!(there.iter().any(|obj| game.get_component_data::<Hostile>("Hostile", obj).is_some()), "nothing at {:?}", spot);
+        }
+    }
+
+    #[test]
+    fn broken_vaults_are_reported() {
+        let (mut game, mut eq) = setup("not a vault");
+        assert_eq!(press(&mut game, &mut eq, '>').map, 0);
+        assert!(messages(&mut game)[0].starts_with("The stairs lead nowhere: "));
+    }
+}
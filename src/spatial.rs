@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+// Which objects stand on each tile, one sparse grid per level. GameManager
+// keeps it in step with WorldPosition components, so lookups by tile don't
+// have to look at every position in the game.
+#[derive(Default)]
+pub struct SpatialIndex {
+    levels: HashMap<u16, HashMap<(u16, u16), Vec<String>>>,
+    // where each object is filed, so it can be found again to move it
+    placed: HashMap<String, (u16, (u16, u16))>
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files `obj` under the tile, moving it from wherever it was before.
+    pub fn place(&mut self, obj: &str, map: u16, pos: (u16, u16)) {
+        if self.placed.get(obj) == Some(&(map, pos)) {
+            return;
+        }
+        self.remove(obj);
+        self.levels.entry(map).or_default().entry(pos).or_default().push(obj.to_string());
+        self.placed.insert(obj.to_string(), (map, pos));
+    }
+
+    pub fn remove(&mut self, obj: &str) {
+        let (map, pos) = match self.placed.remove(obj) {
+            None => return,
+            Some(p) => p
+        };
+        let level = self.levels.get_mut(&map).unwrap();
+        let here = level.get_mut(&pos).unwrap();
+        here.retain(|o| o != obj);
+        // keep the grids sparse
+        if here.is_empty() {
+            level.remove(&pos);
+        }
+    }
+
+    /// Every object on the tile, in the order they arrived.
+    pub fn at(&self, map: u16, pos: (u16, u16)) -> &[String] {
+        self.levels.get(&map)
+            .and_then(|level| level.get(&pos))
+            .map_or(&[], |objs| objs.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameManager;
+    use crate::components::WorldPosition;
+
+    #[test]
+    fn tracks_objects_as_they_move() {
+        let mut index = SpatialIndex::new();
+        index.place("player", 0, (1, 1));
+        index.place("enemy", 0, (1, 1));
+        index.place("key", 1, (1, 1));
+        assert_eq!(index.at(0, (1, 1)), ["player", "enemy"]);
+        assert_eq!(index.at(1, (1, 1)), ["key"]);
+
+        index.place("player", 0, (2, 1));
+        assert_eq!(index.at(0, (1, 1)), ["enemy"]);
+        assert_eq!(index.at(0, (2, 1)), ["player"]);
+
+        index.remove("enemy");
+        index.remove("nobody");
+        assert!(index.at(0, (1, 1)).is_empty());
+        assert!(index.at(5, (0, 0)).is_empty());
+    }
+
+    #[test]
+    fn game_keeps_index_in_step() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&WorldPosition { x: 3, y: 4, map: 0 }, "player");
+        assert_eq!(game.objects_at(0, (3, 4)), ["player"]);
+
+        game.set_component_data(&WorldPosition { x: 3, y: 5, map: 1 }, "player");
+        assert!(game.objects_at(0, (3, 4)).is_empty());
+        assert_eq!(game.objects_at(1, (3, 5)), ["player"]);
+
+        game.remove_object("player");
+        assert!(game.objects_at(1, (3, 5)).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::components::{Actions, InputContext, CombatStats, EquipSlot, Equipment, Equippable, Health, StatusEffects, EffectKind, SIGHT_RADIUS};
+use crate::game::GameManager;
+
+// Stats as they stand once equipment and status effects are counted. The
+// components hold the base values, and anything applying the rules should
+// read these instead.
+
+// what blindness takes off accuracy
+pub const BLIND_ACCURACY: i32 = 25;
+
+/// Whether `obj` is under a status effect of this kind.
+pub fn has_effect(game: &mut GameManager, obj: &str, kind: EffectKind) -> bool {
+    game.get_component_data::<StatusEffects>("StatusEffects", obj).is_some_and(|s| s.has(kind))
+}
+
+/// How many actions `obj` gets each turn: two while hasted, else one.
+pub fn actions_per_turn(game: &mut GameManager, obj: &str) -> u8 {
+    if has_effect(game, obj, EffectKind::Haste) { 2 } else { 1 }
+}
+
+/// Makes the action `obj` is taking last `turns` more turns than usual.
+pub fn slow_action(game: &mut GameManager, obj: &str, turns: u16) {
+    let mut actions: Actions = game.get_component_data("Actions", obj).unwrap_or_default();
+    actions.slowed = actions.slowed.saturating_add(turns);
+    game.set_component_data(&actions, obj);
+}
+
+/// Marks the key the player just pressed on the map as an action. Keys
+/// which do nothing leave it unset, so no time passes for them.
+pub fn take_turn(game: &mut GameManager) {
+    let mut context: InputContext = game.get_component_data("InputContext", "game").unwrap_or_default();
+    context.turn_taken = true;
+    game.set_component_data(&context, "game");
+}
+
+/// Counts an action by `obj`, returning how many turns pass for the rest
+/// of the world: none until it's their last action this turn, then one
+/// plus any they were slowed by.
+pub fn end_action(game: &mut GameManager, obj: &str) -> u16 {
+    let mut actions: Actions = game.get_component_data("Actions", obj).unwrap_or_default();
+    actions.taken += 1;
+    if actions.taken >= actions_per_turn(game, obj) {
+        game.remove_component("Actions", obj);
+        return actions.slowed.saturating_add(1)
+    }
+    game.set_component_data(&actions, obj);
+    0
+}
+
+fn equipped(game: &mut GameManager, obj: &str) -> Vec<Equippable> {
+    let equipment: Equipment = game.get_component_data("Equipment", obj).unwrap_or_default();
+    equipment.items().iter()
+        .filter_map(|item| game.get_component_data("Equippable", item))
+        .collect()
+}
+
+/// How far `obj` sees. The blind only make out what's next to them.
+pub fn sight_radius(game: &mut GameManager, obj: &str) -> u16 {
+    if has_effect(game, obj, EffectKind::Blindness) { 1 } else { SIGHT_RADIUS }
+}
+
+/// `obj`'s CombatStats with what it has equipped applied, and blindness.
+/// Objects without CombatStats start from the defaults.
+pub fn combat_stats(game: &mut GameManager, obj: &str) -> CombatStats {
+    let equipped = equipped(game, obj);
+    derived_stats(game, obj, equipped)
+}
+
+/// combat_stats for an attack made with `weapon`, e.g. something thrown,
+/// which takes the place of whatever `obj` wields.
+pub fn combat_stats_with(game: &mut GameManager, obj: &str, weapon: &str) -> CombatStats {
+    let equipment: Equipment = game.get_component_data("Equipment", obj).unwrap_or_default();
+    let mut items: Vec<Equippable> = EquipSlot::ALL.iter()
+        .filter(|slot| **slot != EquipSlot::Weapon)
+        .filter_map(|slot| equipment.get(*slot))
+        .filter_map(|item| game.get_component_data("Equippable", item))
+        .collect();
+    items.extend(game.get_component_data::<Equippable>("Equippable", weapon));
+    derived_stats(game, obj, items)
+}
+
+fn derived_stats(game: &mut GameManager, obj: &str, equipped: Vec<Equippable>) -> CombatStats {
+    let mut stats: CombatStats = game.get_component_data("CombatStats", obj).unwrap_or_default();
+    for item in equipped {
+        item.apply(&mut stats);
+    }
+    if has_effect(game, obj, EffectKind::Blindness) {
+        stats.accuracy -= BLIND_ACCURACY;
+    }
+    stats
+}
+
+/// `obj`'s Health with its equipment moving max_health (never below 1),
+/// and current_health kept within it.
+pub fn health(game: &mut GameManager, obj: &str) -> Option<Health> {
+    let mut health: Health = game.get_component_data("Health", obj)?;
+    let bonus: i32 = equipped(game, obj).iter().map(|e| e.max_health).sum();
+    let max = (i32::from(health.max_health) + bonus).clamp(1, i32::from(u16::MAX));
+    health.max_health = max as u16;
+    health.current_health = health.current_health.min(health.max_health);
+    Some(health)
+}
+
+/// Brings current_health within the max once equipment is counted, e.g.
+/// after taking off something which raised it.
+pub fn clamp_health(game: &mut GameManager, obj: &str) {
+    let (mut base, derived) = match (game.get_component_data::<Health>("Health", obj), health(game, obj)) {
+        (Some(base), Some(derived)) => (base, derived),
+        _ => return
+    };
+    if derived.current_health < base.current_health {
+        base.current_health = derived.current_health;
+        game.set_component_data(&base, obj);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::StatusEffect;
+
+    #[test]
+    fn equipment_adds_to_base_stats() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&CombatStats { damage: "1d4".to_string(), attack: 1, ..CombatStats::default() }, "player");
+        game.add_component_from_data(&Health { current_health: 10, max_health: 10 }, "player");
+        game.add_component_from_data(&Equippable { damage: Some("1d8".to_string()), accuracy: 5, ..Equippable::new(EquipSlot::Weapon) }, "sword");
+        game.add_component_from_data(&Equippable { defense: 2, max_health: -20, ..Equippable::new(EquipSlot::Ring) }, "ring");
+
+        game.add_component_from_data(&Equipment { weapon: Some("sword".to_string()), ..Equipment::default() }, "player");
+        let stats = combat_stats(&mut game, "player");
+        assert_eq!((stats.damage.as_str(), stats.attack, stats.accuracy, stats.defense), ("1d8", 1, 5, 0));
+        assert_eq!(health(&mut game, "player").unwrap().max_health, 10);
+
+        game.set_component_data(&Equipment { weapon: Some("sword".to_string()), ring: Some("ring".to_string()), ..Equipment::default() }, "player");
+        assert_eq!(combat_stats(&mut game, "player").defense, 2);
+        let cursed = health(&mut game, "player").unwrap();
+        assert_eq!((cursed.current_health, cursed.max_health), (1, 1));
+        // the base is untouched until asked
+        assert_eq!(game.get_component_data::<Health>("Health", "player").unwrap().max_health, 10);
+        clamp_health(&mut game, "player");
+        assert_eq!(game.get_component_data::<Health>("Health", "player").unwrap().current_health, 1);
+    }
+
+    #[test]
+    fn a_weapon_used_takes_the_place_of_the_one_wielded() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&Equippable { damage: Some("1d8".to_string()), accuracy: 5, ..Equippable::new(EquipSlot::Weapon) }, "dagger");
+        game.add_component_from_data(&Equippable { damage: Some("1d4".to_string()), accuracy: 3, ..Equippable::new(EquipSlot::Weapon) }, "knife");
+        game.add_component_from_data(&Equippable { evasion: 1, ..Equippable::new(EquipSlot::Ring) }, "ring");
+        let equipment = Equipment { weapon: Some("dagger".to_string()), ring: Some("ring".to_string()), ..Equipment::default() };
+        game.add_component_from_data(&equipment, "player");
+
+        let thrown = combat_stats_with(&mut game, "player", "knife");
+        assert_eq!((thrown.damage.as_str(), thrown.accuracy, thrown.evasion), ("1d4", 3, 1));
+        let wielded = combat_stats_with(&mut game, "player", "dagger");
+        assert_eq!((wielded.damage.as_str(), wielded.accuracy), ("1d8", 5));
+    }
+
+    #[test]
+    fn haste_gives_two_actions_a_turn() {
+        let mut game = GameManager::new();
+        assert_eq!(end_action(&mut game, "player"), 1);
+        let mut status = StatusEffects::default();
+        status.add(StatusEffect { kind: EffectKind::Haste, turns: 5, potency: 0 });
+        game.add_component_from_data(&status, "player");
+        assert_eq!(end_action(&mut game, "player"), 0);
+        assert_eq!(end_action(&mut game, "player"), 1);
+        assert_eq!(end_action(&mut game, "player"), 0);
+    }
+
+    #[test]
+    fn slow_actions_take_more_turns() {
+        let mut game = GameManager::new();
+        slow_action(&mut game, "player", 1);
+        assert_eq!(end_action(&mut game, "player"), 2);
+        assert_eq!(end_action(&mut game, "player"), 1);
+    }
+}
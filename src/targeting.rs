@@ -0,0 +1,97 @@
+use crate::components::{WorldPosition, TileMap, Health, Hostile, Vector2, line_between, map_object};
+use crate::game::GameManager;
+use crate::stats;
+
+// Attacking from afar: what can be aimed at, and where a shot goes.
+
+/// Everything Hostile with health that `actor` can see on its level,
+/// nearest first.
+pub fn targets_in_sight(game: &mut GameManager, actor: &str) -> Vec<String> {
+    let position: WorldPosition = match game.get_component_data("WorldPosition", actor) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let map: TileMap = match game.get_component_data("TileMap", &map_object(position.map)) {
+        None => return vec![],
+        Some(c) => c
+    };
+    let from = position.as_tuple_2();
+    let radius = stats::sight_radius(game, actor);
+
+    let mut targets = vec![];
+    for pos in map.visible_from(from, radius) {
+        for obj in game.objects_at(position.map, pos).to_vec() {
+            let hostile = game.get_component_data::<Hostile>("Hostile", &obj).is_some();
+            if obj != actor && hostile && game.get_component_data::<Health>("Health", &obj).is_some() {
+                let (dx, dy) = (i32::from(pos.0) - i32::from(from.0), i32::from(pos.1) - i32::from(from.1));
+                targets.push((dx * dx + dy * dy, obj));
+            }
+        }
+    }
+    targets.sort();
+    targets.into_iter().map(|(_, obj)| obj).collect()
+}
+
+pub struct Shot {
+    // the tiles crossed, not counting the start; it stops on the last
+    pub path: Vec<(u16, u16)>,
+    // the first thing with health in the way
+    pub hit: Option<String>
+}
+
+/// Where a shot from `actor` at `to` goes along Bresenham's line: until it
+/// reaches `to`, has gone `range` tiles or strikes something with health.
+/// Walls, glass and closed doors stop it short.
+pub fn line_of_fire(game: &mut GameManager, actor: &str, to: (u16, u16), range: u16) -> Option<Shot> {
+    let position: WorldPosition = game.get_component_data("WorldPosition", actor)?;
+    let map: TileMap = game.get_component_data("TileMap", &map_object(position.map))?;
+
+    let mut path = vec![];
+    for pos in line_between(position.as_tuple_2(), to).into_iter().skip(1).take(usize::from(range)) {
+        if !map.get(pos).is_some_and(|t| t.is_open_to_missiles()) {
+            break;
+        }
+        path.push(pos);
+        let hit = game.objects_at(position.map, pos).to_vec().into_iter()
+            .find(|obj| obj != actor && game.get_component_data::<Health>("Health", obj).is_some());
+        if hit.is_some() {
+            return Some(Shot { path, hit });
+        }
+    }
+    Some(Shot { path, hit: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shots_stop_at_walls_range_and_the_first_thing_hit() {
+        let mut game = GameManager::new();
+        game.add_component_from_data(&TileMap::from_ascii("#########\n#.......#\n#%..#...#\n#.......#\n#########\n").unwrap(), &map_object(0));
+        game.add_component_from_data(&WorldPosition { x: 1, y: 1, map: 0 }, "player");
+        for (obj, x) in [("near", 4), ("far", 6)] {
+            game.add_component_from_data(&WorldPosition { x, y: 1, map: 0 }, obj);
+            game.add_component_from_data(&Health { current_health: 1, max_health: 1 }, obj);
+            game.add_component_from_data(&Hostile, obj);
+        }
+        // something without health is shot past, and something which isn't
+        // hostile isn't aimed at
+        game.add_component_from_data(&WorldPosition { x: 2, y: 1, map: 0 }, "rock");
+        game.add_component_from_data(&WorldPosition { x: 1, y: 3, map: 0 }, "cat");
+        game.add_component_from_data(&Health { current_health: 1, max_health: 1 }, "cat");
+
+        assert_eq!(targets_in_sight(&mut game, "player"), ["near", "far"]);
+
+        let shot = line_of_fire(&mut game, "player", (7, 1), 10).unwrap();
+        assert_eq!((shot.path.len(), shot.hit.as_deref()), (3, Some("near")));
+        let shot = line_of_fire(&mut game, "player", (7, 1), 1).unwrap();
+        assert_eq!((shot.path, shot.hit), (vec![(2, 1)], None));
+        // the wall at 4,2 is in the way
+        let shot = line_of_fire(&mut game, "player", (7, 2), 10).unwrap();
+        assert_eq!((shot.path, shot.hit), (vec![(2, 1), (3, 1)], None));
+        // glass can be seen through but not shot through
+        let shot = line_of_fire(&mut game, "player", (1, 3), 10).unwrap();
+        assert_eq!((shot.path, shot.hit), (vec![], None));
+    }
+}